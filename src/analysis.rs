@@ -0,0 +1,7 @@
+pub mod error;
+pub mod fitness;
+pub mod hill_climb;
+pub mod known_plaintext;
+pub mod crib;
+pub mod stats;
+pub mod dictionary;
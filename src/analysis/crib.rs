@@ -0,0 +1,110 @@
+use crate::analysis::error::AnalysisError;
+use crate::analysis::known_plaintext::{KnownPlaintext, PartialBoard};
+use crate::playfair::alphabet::AlphabetPolicy;
+use crate::playfair::digraph::Digraphs;
+use crate::playfair::error::PlayfairError;
+
+/// Position of a crib that is consistent with the ciphertext.
+pub struct CribPlacement {
+    /// Index of the first crib letter in the ciphertext.
+    pub position: usize,
+    /// Whole plaintext pairs of the crib, matched with their ciphertext pairs.
+    pub pairs: Vec<([char; 2], [char; 2])>,
+    /// A board consistent with the pairs, only the letters of the pairs are placed.
+    pub board: PartialBoard,
+}
+
+/// Returns `false` if the matched pairs break a Playfair invariant: a plaintext pair of
+/// identical letters, a letter encrypting to itself, two pairs sharing a plaintext
+/// or a ciphertext but not both, or reversed pairs not encrypting to reversed pairs.
+fn consistent(pairs: &[([char; 2], [char; 2])]) -> bool {
+    for (i, &(plain, cipher)) in pairs.iter().enumerate() {
+        if plain[0] == plain[1] || plain[0] == cipher[0] || plain[1] == cipher[1] {
+            return false;
+        }
+        for &(other_plain, other_cipher) in &pairs[i + 1..] {
+            let reversed_plain = other_plain == [plain[1], plain[0]];
+            let reversed_cipher = other_cipher == [cipher[1], cipher[0]];
+
+            if (other_plain == plain) != (other_cipher == cipher) || reversed_plain != reversed_cipher {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// Slide a probable word along the ciphertext, at both pair alignments, and return the
+/// positions where it fits. A position fits if the crib breaks no Playfair invariant, and a
+/// board exists that encrypts every whole pair of the crib to the ciphertext. The crib must
+/// not span an inserted filler.
+/// # Arguments
+///
+/// - `ciphertext`: ciphertext to search.
+/// - `crib`: word probably in the plaintext.
+/// - `policy`: alphabet policy of the board.
+pub fn drag(ciphertext: &str, crib: &str, policy: AlphabetPolicy) -> Result<Vec<CribPlacement>, AnalysisError> {
+
+    let ciphertext: Vec<char> = Digraphs::init(policy).cipher_pairs(ciphertext)?.concat();
+    let crib = policy.normalize(crib);
+    if crib.is_empty() {
+        return Err(PlayfairError::EmptyMessage.into());
+    }
+
+    let mut placements = vec![];
+    for position in 0..(ciphertext.len() + 1).saturating_sub(crib.len()) {
+        let cipher = &ciphertext[position..position + crib.len()];
+        if crib.iter().zip(cipher).any(|(p, c)| p == c) {
+            continue;
+        }
+
+        let skip = position % 2;
+        let pairs: Vec<([char; 2], [char; 2])> = crib[skip..].chunks_exact(2)
+            .zip(cipher[skip..].chunks_exact(2))
+            .map(|(p, c)| ([p[0], p[1]], [c[0], c[1]]))
+            .collect();
+        if !consistent(&pairs) {
+            continue;
+        }
+
+        if let Ok(known) = KnownPlaintext::from_pairs(&pairs, policy) {
+            if let Some(board) = known.solve(1).pop() {
+                placements.push(CribPlacement { position, pairs, board });
+            }
+        }
+    }
+    Ok(placements)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::analysis::crib::{consistent, drag};
+    use crate::playfair::alphabet::AlphabetPolicy;
+    use crate::playfair::play::PlayfairCypher;
+
+    #[test]
+    fn invariants() {
+        assert!(consistent(&[(['A', 'B'], ['C', 'D']), (['B', 'A'], ['D', 'C'])]));
+        assert!(!consistent(&[(['A', 'A'], ['C', 'D'])]));
+        assert!(!consistent(&[(['A', 'B'], ['A', 'D'])]));
+        assert!(!consistent(&[(['A', 'B'], ['C', 'D']), (['B', 'A'], ['E', 'F'])]));
+        assert!(!consistent(&[(['A', 'B'], ['C', 'D']), (['A', 'B'], ['C', 'E'])]));
+        assert!(!consistent(&[(['A', 'B'], ['C', 'D']), (['E', 'F'], ['C', 'D'])]));
+    }
+
+    #[test]
+    fn finds_crib_at_both_alignments() {
+        let cypher = PlayfairCypher::init("playfair example").unwrap();
+        let ciphertext = cypher.encrypt("we will meet at the old bridge at dawn").unwrap();
+
+        for (crib, position) in [("bridge", 20), ("oldbridge", 17)] {
+            let placements = drag(&ciphertext, crib, AlphabetPolicy::default()).unwrap();
+            let found = placements.iter().find(|x| x.position == position).unwrap();
+            assert!(placements.len() < ciphertext.len() - crib.len());
+            for (plain, cipher) in &found.pairs {
+                assert_eq!(cypher.encrypt(&plain.iter().collect::<String>()).unwrap(), cipher.iter().collect::<String>());
+            }
+        }
+    }
+}
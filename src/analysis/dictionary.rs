@@ -0,0 +1,140 @@
+use std::io::{self, BufRead};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::analysis::fitness::Fitness;
+use crate::playfair::alphabet::AlphabetPolicy;
+use crate::playfair::digraph::Digraphs;
+use crate::playfair::play::PlayfairCypher;
+
+/// Number of words a thread takes at once, and reports progress after.
+const BATCH: usize = 256;
+
+/// Key phrase tried by the dictionary attack, with its decryption.
+#[derive(Clone, Debug)]
+pub struct DictionaryResult {
+    pub key: String,
+    pub plaintext: String,
+    pub score: f64,
+}
+
+/// Add the result to the ranking, keeping the `best` highest scores.
+fn keep(ranking: &mut Vec<DictionaryResult>, result: DictionaryResult, best: usize) {
+    let index = ranking.partition_point(|x| x.score >= result.score);
+    if index < best {
+        ranking.insert(index, result);
+        ranking.truncate(best);
+    }
+}
+
+/// Try every word of a wordlist as key phrase, and return the decryptions that score best.
+/// Boards are built from each word the way `PlayfairCypher::init` does, words that can not
+/// make a board are skipped. The words are shared out over all cores.
+/// # Arguments
+///
+/// - `ciphertext`: ciphertext to decrypt.
+/// - `wordlist`: candidate key phrases, one per line.
+/// - `fitness`: scores the decryptions.
+/// - `policy`: alphabet policy of the board.
+/// - `best`: number of results to return, highest score first.
+/// - `progress`: called with the number of words tried so far, and the number of words.
+pub fn dictionary_attack<R, F>(ciphertext: &str, wordlist: R, fitness: &Fitness, policy: AlphabetPolicy, best: usize, progress: F) -> io::Result<Vec<DictionaryResult>>
+    where R: BufRead, F: Fn(usize, usize) + Sync {
+
+    let pairs = Digraphs::init(policy).cipher_pairs(ciphertext)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    let mut words = vec![];
+    for line in wordlist.lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            words.push(line.trim().to_string());
+        }
+    }
+
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+    let ranking = Mutex::new(vec![]);
+    let threads = thread::available_parallelism().map_or(1, |x| x.get());
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                let mut local = vec![];
+                loop {
+                    let start = next.fetch_add(BATCH, Ordering::Relaxed);
+                    if start >= words.len() {
+                        break;
+                    }
+                    let batch = &words[start..(start + BATCH).min(words.len())];
+
+                    for word in batch {
+                        let cypher = match PlayfairCypher::init_with_policy(word, policy) {
+                            Ok(cypher) => cypher,
+                            Err(_) => continue
+                        };
+                        let plaintext: Option<String> = pairs.iter()
+                            .map(|x| cypher.decode_pair(*x).ok())
+                            .collect::<Option<Vec<[char; 2]>>>()
+                            .map(|x| x.concat().into_iter().collect());
+
+                        if let Some(plaintext) = plaintext {
+                            let score = fitness.score(&plaintext);
+                            keep(&mut local, DictionaryResult { key: word.clone(), plaintext, score }, best);
+                        }
+                    }
+                    progress(done.fetch_add(batch.len(), Ordering::Relaxed) + batch.len(), words.len());
+                }
+
+                let mut ranking = ranking.lock().expect("no thread panicked while holding the ranking");
+                for result in local {
+                    keep(&mut ranking, result, best);
+                }
+            });
+        }
+    });
+
+    Ok(ranking.into_inner().expect("no thread panicked while holding the ranking"))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::io::{self, Cursor};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use crate::analysis::dictionary::dictionary_attack;
+    use crate::analysis::fitness::Fitness;
+    use crate::playfair::alphabet::AlphabetPolicy;
+    use crate::playfair::play::PlayfairCypher;
+
+    #[test]
+    fn finds_key_in_wordlist() {
+        let ciphertext = PlayfairCypher::init("harbour").unwrap()
+            .encrypt("the convoy leaves the harbour at first light").unwrap();
+        let mut wordlist: Vec<String> = (0..2000)
+            .map(|x| x.to_string().bytes().map(|d| (d - b'0' + b'a') as char).collect())
+            .collect();
+        wordlist.extend(["keyword", "harbour", "don't", "monarchy"].map(String::from));
+        let calls = AtomicUsize::new(0);
+        let total = AtomicUsize::new(0);
+
+        let results = dictionary_attack(&ciphertext, Cursor::new(wordlist.join("\n")), &Fitness::english(), AlphabetPolicy::default(), 3, |done, words| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            total.fetch_max(done, Ordering::Relaxed);
+            assert_eq!(2004, words);
+        }).unwrap();
+
+        assert_eq!(3, results.len());
+        assert_eq!("harbour", results[0].key);
+        assert_eq!("THECONVOYLEAVESTHEHARBOURATFIRSTLIGHTX", results[0].plaintext);
+        assert!(results[0].score > results[1].score && results[1].score >= results[2].score);
+        assert!(calls.load(Ordering::Relaxed) >= 2004 / 256);
+        assert_eq!(2004, total.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn rejects_invalid_ciphertext() {
+        let err = dictionary_attack("ABC", Cursor::new("key"), &Fitness::english(), AlphabetPolicy::default(), 1, |_, _| {}).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+    }
+}
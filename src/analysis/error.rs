@@ -0,0 +1,47 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::playfair::error::PlayfairError;
+
+/// Errors returned by the cryptanalysis tools.
+#[derive(Clone, PartialEq, Debug)]
+pub enum AnalysisError {
+    /// The board or cypher rejected the input.
+    Playfair(PlayfairError),
+    /// Plaintext and ciphertext of a crib have a different number of pairs.
+    CribLengthMismatch { plaintext: usize, ciphertext: usize },
+    /// A plaintext pair can never encrypt to its ciphertext pair.
+    InvalidCribPair { plaintext: String, ciphertext: String },
+    /// A setting of the key search is out of range.
+    InvalidSearchOption { setting: &'static str, reason: &'static str },
+}
+
+impl fmt::Display for AnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnalysisError::Playfair(err) => write!(f, "{}", err),
+            AnalysisError::CribLengthMismatch { plaintext, ciphertext } => {
+                write!(f, "crib has {} plaintext pairs but {} ciphertext pairs", plaintext, ciphertext)
+            },
+            AnalysisError::InvalidCribPair { plaintext, ciphertext } => {
+                write!(f, "'{}' can not encrypt to '{}'", plaintext, ciphertext)
+            },
+            AnalysisError::InvalidSearchOption { setting, reason } => write!(f, "invalid {}: {}", setting, reason),
+        }
+    }
+}
+
+impl Error for AnalysisError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AnalysisError::Playfair(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<PlayfairError> for AnalysisError {
+    fn from(err: PlayfairError) -> Self {
+        AnalysisError::Playfair(err)
+    }
+}
@@ -0,0 +1,209 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// English quadgram counts, from public domain novels and essays by Austen, Dickens, Melville and others.
+const ENGLISH_QUADGRAMS: &str = include_str!("english_quadgrams.txt");
+/// English trigram counts, from the same texts as the quadgrams.
+const ENGLISH_TRIGRAMS: &str = include_str!("english_trigrams.txt");
+
+/// Scores how much a text looks like English, as the sum of the log probabilities
+/// of its n-grams. Higher scores are more English-like.
+/// http://practicalcryptography.com/cryptanalysis/text-characterisation/quadgrams/
+pub struct Fitness {
+    n: usize,
+    log_probs: Vec<f32>,
+    floor: f32,
+}
+
+impl Fitness {
+
+    /// Returns the model built from the embedded english quadgram table.
+    pub fn english() -> Self {
+        Self::from_table(ENGLISH_QUADGRAMS).expect("embedded quadgram table is valid")
+    }
+
+    /// Returns the model built from the embedded english trigram table. Less precise than
+    /// quadgrams, but more forgiving on short texts.
+    pub fn english_trigrams() -> Self {
+        Self::from_table(ENGLISH_TRIGRAMS).expect("embedded trigram table is valid")
+    }
+
+    /// Returns the model built from a table file, see `from_table`.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_table(&fs::read_to_string(path)?)
+    }
+
+    /// Returns the model built from a table of `NGRAM COUNT` lines, or of `NGRAM LOG_PROB`
+    /// lines holding negative log10 probabilities, such as `TION -2.54`. The format is told
+    /// by the sign of the values, and all lines must use the same one. All n-grams must be
+    /// letters and have the same length, up to 5. N-grams not in the table get the log
+    /// probability of a hundredth of an occurrence, or of a hundredth of the rarest n-gram
+    /// of a log probability table.
+    /// Fails with `InvalidData` if the table is empty, mixes formats or a line is malformed.
+    pub fn from_table(table: &str) -> io::Result<Self> {
+
+        let invalid = |line: usize, reason: &str| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", line, reason))
+        };
+
+        let mut values = vec![];
+        for (number, line) in table.lines().enumerate().filter(|x| !x.1.trim().is_empty()) {
+            let mut fields = line.split_whitespace();
+            let ngram = fields.next().unwrap_or_default().to_ascii_uppercase();
+            let value: f64 = fields.next()
+                .and_then(|x| x.parse().ok())
+                .filter(|&x: &f64| x != 0.0 && x.is_finite())
+                .ok_or_else(|| invalid(number + 1, "expected a positive count or a negative log probability"))?;
+
+            let (n, logs) = values.first().map_or((ngram.len(), value < 0.0), |x: &(String, f64)| (x.0.len(), x.1 < 0.0));
+            if ngram.len() != n || n > 5 || Self::index(ngram.as_bytes()).is_none() {
+                return Err(invalid(number + 1, "expected an n-gram of up to 5 letters, as long as the others"));
+            }
+            if (value < 0.0) != logs {
+                return Err(invalid(number + 1, "counts and log probabilities can not be mixed"));
+            }
+            values.push((ngram, value));
+        }
+
+        let n = values.first().ok_or_else(|| invalid(0, "table is empty"))?.0.len();
+        let floor = if values[0].1 < 0.0 {
+            values.iter().map(|x| x.1).fold(f64::INFINITY, f64::min) - 2.0
+        } else {
+            let total: f64 = values.iter().map(|x| x.1).sum();
+            for value in values.iter_mut() {
+                value.1 = (value.1 / total).log10();
+            }
+            (0.01 / total).log10()
+        };
+        let mut log_probs = vec![floor as f32; 26usize.pow(n as u32)];
+
+        for (ngram, log_prob) in values {
+            if let Some(index) = Self::index(ngram.as_bytes()) {
+                log_probs[index] = log_prob as f32;
+            }
+        }
+        Ok(Fitness { n, log_probs, floor: floor as f32 })
+    }
+
+    /// Returns the length of the n-grams of the model.
+    pub fn order(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the table index for an n-gram of uppercase letters.
+    fn index(ngram: &[u8]) -> Option<usize> {
+        ngram.iter().try_fold(0, |acc, &x| {
+            x.is_ascii_uppercase().then(|| acc * 26 + (x - b'A') as usize)
+        })
+    }
+
+    /// Returns the log probability of the text. Only the letters of the text are scored,
+    /// texts shorter than one n-gram score the floor.
+    pub fn score(&self, text: &str) -> f64 {
+        let high = self.log_probs.len() / 26;
+        let mut window = [0; 8];
+        let mut index = 0;
+        let mut letters = 0;
+        let mut score = 0.0;
+
+        // The index of the last n letters is kept up to date by taking out the oldest
+        // letter and adding the new one, the last letters are kept in a small ring.
+        for letter in text.bytes().filter(|x| x.is_ascii_alphabetic()) {
+            let value = (letter.to_ascii_uppercase() - b'A') as usize;
+            if letters >= self.n {
+                index -= window[(letters - self.n) % 8] * high;
+            }
+            index = index * 26 + value;
+            window[letters % 8] = value;
+            letters += 1;
+            if letters >= self.n {
+                score += self.log_probs[index] as f64;
+            }
+        }
+
+        if letters < self.n {
+            return self.floor as f64;
+        }
+        score
+    }
+
+    /// Returns the candidates with their scores, most English-like first. Used to pick
+    /// the right plaintext out of decryptions under many trial keys.
+    pub fn rank<T: AsRef<str>>(&self, candidates: impl IntoIterator<Item = T>) -> Vec<(T, f64)> {
+        let mut ranked: Vec<(T, f64)> = candidates.into_iter()
+            .map(|x| {
+                let score = self.score(x.as_ref());
+                (x, score)
+            })
+            .collect();
+
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::io;
+    use crate::analysis::fitness::Fitness;
+    use crate::playfair::play::PlayfairCypher;
+
+    #[test]
+    fn english_scores_higher() {
+        let fitness = Fitness::english();
+        let english = fitness.score("Attack the east wall of the castle at dawn");
+        let scrambled = fitness.score("Tcaakt hte ates lwla fo het lsteac ta nwda");
+        assert!(english > scrambled);
+    }
+
+    #[test]
+    fn score_ignores_case_and_punctuation() {
+        let fitness = Fitness::english();
+        assert_eq!(fitness.score("THEWALL"), fitness.score("the, wall!"));
+    }
+
+    #[test]
+    fn trigrams_score_higher() {
+        let fitness = Fitness::english_trigrams();
+        assert_eq!(3, fitness.order());
+        assert!(fitness.score("meet me at noon") > fitness.score("eemt em ta onno"));
+    }
+
+    #[test]
+    fn from_table() {
+        let fitness = Fitness::from_table("TH 60\nHE 30\n\nIN 10\n").unwrap();
+        assert_eq!(2, fitness.order());
+        assert!((fitness.score("the") - (0.6f64.log10() + 0.3f64.log10())).abs() < 1e-6);
+    }
+
+    #[test]
+    fn from_log_table() {
+        let fitness = Fitness::from_table("TION -2.54\nNTHE -2.16\nTHER -2.07\n").unwrap();
+        assert_eq!(4, fitness.order());
+        assert!((fitness.score("nther") - (-2.16 - 2.07)).abs() < 1e-6);
+        assert!((fitness.score("ntion") - (-4.54 - 2.54)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn from_table_errors() {
+        for table in ["", "THE 10\nTH 5", "THE ten", "THE 0", "THE inf", "T3E 10", "ABCDEF 1", "THE 10\nAND -1.5"] {
+            let err = Fitness::from_table(table).err().unwrap();
+            assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        }
+        assert_eq!(io::ErrorKind::NotFound, Fitness::load("no/such/table.txt").err().unwrap().kind());
+    }
+
+    #[test]
+    fn rank_trial_keys() {
+        let ciphertext = PlayfairCypher::init("playfair example").unwrap()
+            .encrypt("Hide the gold in the tree stump").unwrap();
+        let decryptions = ["keyword", "playfair example", "monarchy"].iter()
+            .map(|key| PlayfairCypher::init(key).unwrap().decrypt(&ciphertext).unwrap());
+
+        let ranked = Fitness::english().rank(decryptions);
+        assert_eq!("HIDETHEGOLDINTHETREXESTUMP", ranked[0].0);
+        assert!(ranked[0].1 > ranked[1].1 && ranked[1].1 >= ranked[2].1);
+    }
+}
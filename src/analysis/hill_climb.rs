@@ -0,0 +1,339 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::analysis::error::AnalysisError;
+use crate::playfair::alphabet::AlphabetPolicy;
+use crate::playfair::array::Board;
+use crate::playfair::digraph::Digraphs;
+
+/// Settings of the simulated annealing search.
+#[derive(Clone, Debug)]
+pub struct HillClimbOptions {
+    /// Starting temperature. The higher it is, the more often worse boards are accepted.
+    /// Derived from the length of the ciphertext if `None`, see `start_temperature`.
+    pub temperature: Option<f64>,
+    /// How much the temperature drops after each round.
+    pub cooling: f64,
+    /// Number of boards tried per round.
+    pub iterations: usize,
+    /// Number of independent searches, the best result of all is kept.
+    pub restarts: usize,
+    /// Seed of the random number generator, random if `None`.
+    pub seed: Option<u64>,
+    /// Alphabet policy the ciphertext was encrypted with.
+    pub policy: AlphabetPolicy,
+}
+
+impl Default for HillClimbOptions {
+    fn default() -> Self {
+        HillClimbOptions {
+            temperature: None,
+            cooling: 0.2,
+            iterations: 10_000,
+            restarts: 1,
+            seed: None,
+            policy: AlphabetPolicy::default(),
+        }
+    }
+}
+
+/// Most rounds of annealing a search may run, the defaults need about 150.
+const MAX_ROUNDS: usize = 100_000;
+
+impl HillClimbOptions {
+    /// Returns the starting temperature and the number of rounds of annealing, one per cooling
+    /// step down to zero, for a ciphertext of `length` symbols. Fails if a setting would keep
+    /// the search from running, or have it run for more than `MAX_ROUNDS` rounds.
+    fn schedule(&self, length: usize) -> Result<(f64, usize), AnalysisError> {
+        if self.temperature.is_some_and(|x| !(x > 0.0 && x.is_finite())) {
+            return Err(AnalysisError::InvalidSearchOption { setting: "temperature", reason: "must be positive and finite" });
+        }
+        if !(self.cooling > 0.0 && self.cooling.is_finite()) {
+            return Err(AnalysisError::InvalidSearchOption { setting: "cooling", reason: "must be positive and finite" });
+        }
+        if self.iterations == 0 {
+            return Err(AnalysisError::InvalidSearchOption { setting: "iterations", reason: "must be at least 1" });
+        }
+        if self.restarts == 0 {
+            return Err(AnalysisError::InvalidSearchOption { setting: "restarts", reason: "must be at least 1" });
+        }
+
+        let temperature = self.temperature.unwrap_or_else(|| start_temperature(length));
+        let rounds = (temperature / self.cooling).ceil();
+        if rounds > MAX_ROUNDS as f64 {
+            return Err(AnalysisError::InvalidSearchOption { setting: "cooling", reason: "too slow for the temperature, over 100000 rounds" });
+        }
+        Ok((temperature, rounds as usize))
+    }
+}
+
+/// Returns a starting temperature suited to the length of the ciphertext. Scores of longer
+/// texts spread wider, so they need a hotter start to leave a bad board.
+/// The scale, 10 at 84 letters and about 31 at 324, works well with log10 quadgram scores.
+/// # Arguments
+///
+/// - `length`: number of symbols of the ciphertext.
+pub fn start_temperature(length: usize) -> f64 {
+    (10.0 + 0.087 * (length as f64 - 84.0)).max(1.0)
+}
+
+/// Best board found by the search.
+pub struct HillClimbResult {
+    pub board: Board<char>,
+    pub plaintext: String,
+    pub score: f64,
+}
+
+/// Returns the key changed in a random way. Mostly two cells are swapped, now and then
+/// rows or columns are swapped, or the whole board is transposed or reversed.
+/// # Arguments
+///
+/// - `key`: board symbols, row by row.
+/// - `size`: number of rows and columns of the board.
+fn mutate<R: Rng>(key: &[char], size: usize, rng: &mut R) -> Vec<char> {
+    let mut child = key.to_vec();
+    let a = rng.gen_range(0..size);
+    let b = rng.gen_range(0..size);
+
+    match rng.gen_range(0..50) {
+        0 => for i in 0..size { child.swap(a * size + i, b * size + i) },
+        1 => for i in 0..size { child.swap(i * size + a, i * size + b) },
+        2 => for i in 0..size { for j in 0..size { child[i * size + j] = key[j * size + i] } },
+        3 => child.reverse(),
+        4 => for i in 0..size { for j in 0..size { child[i * size + j] = key[(size - 1 - i) * size + j] } },
+        5 => for i in 0..size { for j in 0..size { child[i * size + j] = key[i * size + size - 1 - j] } },
+        _ => child.swap(rng.gen_range(0..key.len()), rng.gen_range(0..key.len()))
+    }
+    child
+}
+
+/// Returns the ciphertext decrypted with the board spelled out by the key. The search
+/// decrypts far too many candidates to build a cypher for each, so the pairs are decoded
+/// straight from the key, with the cell of every symbol kept in a plain array.
+/// # Arguments
+///
+/// - `key`: board symbols, row by row.
+/// - `pairs`: ciphertext pairs, every symbol part of the key.
+/// - `size`: number of rows and columns of the board.
+fn decrypt(key: &[char], pairs: &[[char; 2]], size: usize) -> String {
+    let mut cells = [[0; 2]; 128];
+    for (i, symbol) in key.iter().enumerate() {
+        cells[*symbol as usize] = [i / size, i % size];
+    }
+    let previous = |x: usize| if x == 0 { size - 1 } else { x - 1 };
+
+    let mut data = String::with_capacity(pairs.len() * 2);
+    for pair in pairs {
+        let [[r1, c1], [r2, c2]] = pair.map(|x| cells[x as usize]);
+        let cells = if r1 == r2 {
+            [r1 * size + previous(c1), r2 * size + previous(c2)]
+        } else if c1 == c2 {
+            [previous(r1) * size + c1, previous(r2) * size + c2]
+        } else {
+            [r1 * size + c2, r2 * size + c1]
+        };
+        data.push(key[cells[0]]);
+        data.push(key[cells[1]]);
+    }
+    data
+}
+
+/// Returns the key improved greedily, until no swap of two cells, rows or columns scores better.
+/// Polishes a key that simulated annealing brought close to the solution.
+fn polish<F>(key: Vec<char>, score: f64, pairs: &[[char; 2]], fitness: &F, size: usize) -> (Vec<char>, f64) where F: Fn(&str) -> f64 {
+
+    let mut best = (key, score);
+    let mut improved = true;
+
+    while improved {
+        improved = false;
+        let mut candidates = vec![];
+
+        for a in 0..best.0.len() {
+            for b in a+1..best.0.len() {
+                let mut child = best.0.clone();
+                child.swap(a, b);
+                candidates.push(child);
+            }
+        }
+        for a in 0..size {
+            for b in a+1..size {
+                let mut rows = best.0.clone();
+                let mut columns = best.0.clone();
+                for i in 0..size {
+                    rows.swap(a * size + i, b * size + i);
+                    columns.swap(i * size + a, i * size + b);
+                }
+                candidates.push(rows);
+                candidates.push(columns);
+            }
+        }
+
+        for child in candidates {
+            let score = fitness(&decrypt(&child, pairs, size));
+            if score > best.1 {
+                best = (child, score);
+                improved = true;
+            }
+        }
+    }
+    best
+}
+
+/// Returns the best key found by one run of simulated annealing, and its score.
+/// The temperature drops by `cooling` each round, from `start` over the given number of rounds.
+fn anneal<F, R>(pairs: &[[char; 2]], fitness: &F, options: &HillClimbOptions, (start, rounds): (f64, usize), rng: &mut R) -> (Vec<char>, f64) where F: Fn(&str) -> f64, R: Rng {
+
+    let size = options.policy.size();
+    let mut parent = options.policy.symbols();
+    parent.shuffle(rng);
+    let mut parent_score = fitness(&decrypt(&parent, pairs, size));
+    let mut best = (parent.clone(), parent_score);
+
+    for round in 0..rounds {
+        let temperature = start - round as f64 * options.cooling;
+        for _ in 0..options.iterations {
+            let child = mutate(&parent, size, rng);
+            let score = fitness(&decrypt(&child, pairs, size));
+            let delta = score - parent_score;
+
+            if delta >= 0.0 || rng.gen::<f64>() < (delta / temperature).exp() {
+                parent = child;
+                parent_score = score;
+            }
+            if parent_score > best.1 {
+                best = (parent.clone(), parent_score);
+            }
+        }
+    }
+    polish(best.0, best.1, pairs, fitness, size)
+}
+
+/// Recover the board of a Playfair ciphertext, without knowing any of its plaintext.
+/// Random boards are improved by simulated annealing, keeping the board whose
+/// decryption scores best with the fitness function. Annealing does not always find the
+/// solution, more iterations and restarts improve the odds.
+/// https://en.wikipedia.org/wiki/Simulated_annealing
+/// # Arguments
+///
+/// - `ciphertext`: ciphertext, as produced by `PlayfairCypher::playfair_encode`.
+/// - `fitness`: scores candidate plaintexts, higher is better. Typically the log probability
+///   of the text under a model of the language of the plaintext.
+/// - `options`: settings of the search.
+pub fn hill_climb<F>(ciphertext: &str, fitness: F, options: &HillClimbOptions) -> Result<HillClimbResult, AnalysisError> where F: Fn(&str) -> f64 {
+
+    let policy = options.policy;
+    let size = policy.size();
+    let pairs = Digraphs::init(policy).cipher_pairs(ciphertext)?;
+    let schedule = options.schedule(pairs.len() * 2)?;
+    let mut rng = match options.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy()
+    };
+
+    let mut best = anneal(&pairs, &fitness, options, schedule, &mut rng);
+    for _ in 1..options.restarts {
+        let candidate = anneal(&pairs, &fitness, options, schedule, &mut rng);
+        if candidate.1 > best.1 {
+            best = candidate;
+        }
+    }
+
+    let (key, score) = best;
+    Ok(HillClimbResult {
+        plaintext: decrypt(&key, &pairs, size),
+        board: Board::init(&key, size, size)?,
+        score,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use crate::analysis::error::AnalysisError;
+    use crate::analysis::fitness::Fitness;
+    use crate::analysis::hill_climb::{decrypt, hill_climb, mutate, polish, HillClimbOptions};
+    use crate::playfair::digraph::Digraphs;
+    use crate::playfair::play::PlayfairCypher;
+
+    #[test]
+    fn mutate_keeps_symbols() {
+        let key: Vec<char> = "ABCDEFGHIKLMNOPQRSTUVWXYZ".chars().collect();
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for _ in 0..200 {
+            let mut child = mutate(&key, 5, &mut rng);
+            child.sort();
+            assert_eq!(key, child);
+        }
+    }
+
+    #[test]
+    fn decrypt_matches_cypher() {
+        let cypher = PlayfairCypher::init("Playfair example").unwrap();
+        let ciphertext = cypher.encrypt("Hide the gold in the tree stump").unwrap();
+        let key: Vec<char> = "PLAYFIREXMBCDGHKNOQSTUVWZ".chars().collect();
+        let pairs = Digraphs::init(Default::default()).cipher_pairs(&ciphertext).unwrap();
+
+        let expected: String = pairs.iter().flat_map(|x| cypher.decode_pair(*x).unwrap()).collect();
+        assert_eq!(decrypt(&key, &pairs, 5), expected);
+        assert_eq!(decrypt(&key, &pairs, 5), "HIDETHEGOLDINTHETREXESTUMP");
+    }
+
+    #[test]
+    fn options_are_checked() {
+        let ciphertext = "BMODZBXDNABEKUDMUIXMMOUVIF";
+        let fitness = |_: &str| 0.0;
+
+        for options in [
+            HillClimbOptions { cooling: 0.0, ..Default::default() },
+            HillClimbOptions { cooling: -1.0, ..Default::default() },
+            HillClimbOptions { temperature: Some(f64::NAN), ..Default::default() },
+            HillClimbOptions { temperature: Some(f64::INFINITY), ..Default::default() },
+            HillClimbOptions { iterations: 0, ..Default::default() },
+            HillClimbOptions { restarts: 0, ..Default::default() },
+            HillClimbOptions { temperature: Some(1e20), cooling: 1.0, ..Default::default() },
+            HillClimbOptions { temperature: Some(1e6), cooling: 1e-6, ..Default::default() },
+        ] {
+            assert!(matches!(hill_climb(ciphertext, fitness, &options), Err(AnalysisError::InvalidSearchOption { .. })));
+        }
+    }
+
+    const TALE: &str = "It was the best of times, it was the worst of times, it was the age of wisdom, \
+        it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity, \
+        it was the season of Light, it was the season of Darkness, it was the spring of hope, \
+        it was the winter of despair, we had everything before us, we had nothing before us, \
+        we were all going direct to Heaven, we were all going direct the other way";
+
+    #[test]
+    fn polish_finishes_near_solution() {
+        let cypher = PlayfairCypher::init("charles dickens novel").unwrap();
+        let ciphertext = cypher.encrypt(TALE).unwrap();
+        let pairs = Digraphs::init(Default::default()).cipher_pairs(&ciphertext).unwrap();
+        let fitness = Fitness::english();
+        let score = |text: &str| fitness.score(text);
+
+        let key: Vec<char> = cypher.board().to_string().chars().filter(|x| x.is_ascii_uppercase()).collect();
+        let mut near = key.clone();
+        near.swap(3, 17);
+        let (found, _) = polish(near.clone(), score(&decrypt(&near, &pairs, 5)), &pairs, &score, 5);
+        assert_eq!(decrypt(&found, &pairs, 5), decrypt(&key, &pairs, 5));
+    }
+
+    #[test]
+    #[ignore = "slow, run with `cargo test --release -- --ignored`"]
+    fn hill_climb_recovers_board() {
+        let cypher = PlayfairCypher::init("charles dickens novel").unwrap();
+        let ciphertext = cypher.encrypt(TALE).unwrap();
+        let options = HillClimbOptions { restarts: 4, seed: Some(1), ..Default::default() };
+        let fitness = Fitness::english();
+
+        let result = hill_climb(&ciphertext, |text| fitness.score(text), &options).unwrap();
+        assert_eq!(result.plaintext, cypher.decrypt(&ciphertext).unwrap());
+        let found = PlayfairCypher::from_board(result.board, options.policy).unwrap();
+        assert_eq!(found.encrypt(TALE).unwrap(), ciphertext);
+    }
+}
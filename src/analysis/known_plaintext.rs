@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+use crate::analysis::error::AnalysisError;
+use crate::playfair::alphabet::AlphabetPolicy;
+use crate::playfair::array::{Board, BoardShape};
+use crate::playfair::digraph::Digraphs;
+use crate::playfair::error::PlayfairError;
+
+/// Board with only some of its cells known.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PartialBoard {
+    size: usize,
+    cells: Vec<Option<char>>,
+    positions: HashMap<char, [usize; 2]>,
+}
+
+impl PartialBoard {
+
+    /// Initialize an empty board of `size` rows and columns.
+    pub fn init(size: usize) -> Self {
+        PartialBoard { size, cells: vec![None; size * size], positions: HashMap::new() }
+    }
+
+    /// Returns the number of rows and columns.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the symbol at the given position, `None` if it is not known.
+    pub fn get_element(&self, row: usize, column: usize) -> Option<char> {
+        self.cells[row * self.size + column]
+    }
+
+    /// Returns the position of the symbol, `None` if it is not placed.
+    pub fn get_position(&self, symbol: char) -> Option<[usize; 2]> {
+        self.positions.get(&symbol).copied()
+    }
+
+    /// Returns the number of known cells.
+    pub fn known(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns the board, if every cell is known.
+    pub fn to_board(&self) -> Option<Board<char>> {
+        let data: Option<Vec<char>> = self.cells.iter().copied().collect();
+        Board::init(&data?, self.size, self.size).ok()
+    }
+
+    /// Place the symbol at the given position. Returns `false` if the symbol is placed
+    /// elsewhere, or another symbol is in the cell.
+    pub fn place(&mut self, symbol: char, position: [usize; 2]) -> bool {
+        let cell = &mut self.cells[position[0] * self.size + position[1]];
+
+        match (self.positions.get(&symbol), *cell) {
+            (Some(placed), _) => *placed == position,
+            (None, Some(_)) => false,
+            (None, None) => {
+                *cell = Some(symbol);
+                self.positions.insert(symbol, position);
+                true
+            }
+        }
+    }
+
+    /// Returns the positions the pair at the given positions encrypts to.
+    fn encode(&self, positions: [[usize; 2]; 2]) -> [[usize; 2]; 2] {
+        let [p1, p2] = positions;
+
+        match BoardShape::from_positions(p1, p2) {
+            BoardShape::ROW => positions.map(|p| [p[0], (p[1] + 1) % self.size]),
+            BoardShape::COLUMN => positions.map(|p| [(p[0] + 1) % self.size, p[1]]),
+            BoardShape::RECTANGLE => [[p1[0], p2[1]], [p2[0], p1[1]]]
+        }
+    }
+
+    /// Returns the positions the pair at the given positions decrypts to.
+    fn decode(&self, positions: [[usize; 2]; 2]) -> [[usize; 2]; 2] {
+        let [p1, p2] = positions;
+
+        match BoardShape::from_positions(p1, p2) {
+            BoardShape::ROW => positions.map(|p| [p[0], (p[1] + self.size - 1) % self.size]),
+            BoardShape::COLUMN => positions.map(|p| [(p[0] + self.size - 1) % self.size, p[1]]),
+            BoardShape::RECTANGLE => [[p1[0], p2[1]], [p2[0], p1[1]]]
+        }
+    }
+}
+
+impl Display for PartialBoard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.cells.chunks(self.size) {
+            for cell in row {
+                write!(f, "{} ", cell.unwrap_or('.'))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Plaintext pairs matched with the ciphertext pairs they encrypt to. Every match constrains
+/// the board: the letters form a row, a column or a rectangle, and the rules of
+/// `PlayfairCypher::process_pair` tie the positions of the plaintext to those of the ciphertext.
+pub struct KnownPlaintext {
+    policy: AlphabetPolicy,
+    pairs: Vec<([char; 2], [char; 2])>,
+}
+
+impl KnownPlaintext {
+
+    /// Initialize from a plaintext fragment and its aligned ciphertext. The plaintext is
+    /// broken into pairs the way encryption does, inserting fillers.
+    /// # Arguments
+    ///
+    /// - `plaintext`: plaintext fragment, starting on a pair boundary.
+    /// - `ciphertext`: ciphertext of the fragment.
+    /// - `policy`: alphabet policy of the board.
+    pub fn init(plaintext: &str, ciphertext: &str, policy: AlphabetPolicy) -> Result<Self, AnalysisError> {
+        let digraphs = Digraphs::init(policy);
+        let plaintext = digraphs.pairs(plaintext)?;
+        let ciphertext = digraphs.cipher_pairs(ciphertext)?;
+
+        if plaintext.len() != ciphertext.len() {
+            return Err(AnalysisError::CribLengthMismatch { plaintext: plaintext.len(), ciphertext: ciphertext.len() });
+        }
+        Self::from_pairs(&plaintext.into_iter().zip(ciphertext).collect::<Vec<_>>(), policy)
+    }
+
+    /// Initialize from matched pairs, as `(plaintext, ciphertext)`. Fails if a pair can never
+    /// encrypt to its match: a pair of identical letters, or a letter encrypting to itself.
+    pub fn from_pairs(pairs: &[([char; 2], [char; 2])], policy: AlphabetPolicy) -> Result<Self, AnalysisError> {
+        let mut checked: Vec<([char; 2], [char; 2])> = vec![];
+
+        for &(plain, cipher) in pairs {
+            for symbol in plain.iter().chain(cipher.iter()) {
+                if !policy.contains(*symbol) {
+                    return Err(PlayfairError::SymbolNotOnBoard(symbol.to_string()).into());
+                }
+            }
+            if plain[0] == plain[1] || cipher[0] == cipher[1] || plain[0] == cipher[0] || plain[1] == cipher[1] {
+                return Err(AnalysisError::InvalidCribPair { plaintext: plain.iter().collect(), ciphertext: cipher.iter().collect() });
+            }
+            if !checked.contains(&(plain, cipher)) {
+                checked.push((plain, cipher));
+            }
+        }
+        Ok(KnownPlaintext { policy, pairs: checked })
+    }
+
+    /// Returns the matched pairs, without repeats.
+    pub fn pairs(&self) -> &[([char; 2], [char; 2])] {
+        &self.pairs
+    }
+
+    /// Returns the boards consistent with every matched pair, at most `limit` of them. Only the
+    /// letters of the pairs are placed. Shifting the rows or columns of a board around gives
+    /// the same cipher, so the first letter is always placed in the top left corner.
+    /// The result is empty if the pairs contradict each other.
+    pub fn solve(&self, limit: usize) -> Vec<PartialBoard> {
+        let mut solutions = vec![];
+        let mut board = PartialBoard::init(self.policy.size());
+
+        match self.pairs.first() {
+            Some(pair) if limit > 0 => {
+                board.place(pair.0[0], [0, 0]);
+                self.search(board, limit, &mut solutions);
+            },
+            _ => {}
+        }
+        solutions
+    }
+
+    /// Returns `false` if the board breaks one of the pairs. Places the letters that follow
+    /// from the known ones, until no more can be placed.
+    fn propagate(&self, board: &mut PartialBoard) -> bool {
+        let mut placed = board.known() + 1;
+
+        while placed != board.known() {
+            placed = board.known();
+
+            for (plain, cipher) in &self.pairs {
+                let p = plain.map(|x| board.get_position(x));
+                let c = cipher.map(|x| board.get_position(x));
+
+                if let [Some(p1), Some(p2)] = p {
+                    let [c1, c2] = board.encode([p1, p2]);
+                    if !(board.place(cipher[0], c1) && board.place(cipher[1], c2)) {
+                        return false;
+                    }
+                }
+                if let [Some(c1), Some(c2)] = c {
+                    let [p1, p2] = board.decode([c1, c2]);
+                    if !(board.place(plain[0], p1) && board.place(plain[1], p2)) {
+                        return false;
+                    }
+                }
+                // A letter encrypts to another letter of its row, or the letter below it.
+                for (p, c) in p.iter().zip(c.iter()) {
+                    if let (Some(p), Some(c)) = (p, c) {
+                        let row = p[0] == c[0] && p[1] != c[1];
+                        let below = c[0] == (p[0] + 1) % board.size() && p[1] == c[1];
+                        if !(row || below) {
+                            return false;
+                        }
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Add the consistent boards that follow from `board` to `solutions`, until there are `limit`.
+    fn search(&self, mut board: PartialBoard, limit: usize, solutions: &mut Vec<PartialBoard>) {
+        if solutions.len() >= limit || !self.propagate(&mut board) {
+            return;
+        }
+
+        // The next letter to place, preferably one whose counterpart is known.
+        let unplaced = |x: &char| board.get_position(*x).is_none();
+        let mut next = None;
+        for (plain, cipher) in &self.pairs {
+            for i in 0..2 {
+                for (letter, other) in [(plain[i], cipher[i]), (cipher[i], plain[i])] {
+                    if unplaced(&letter) && !unplaced(&other) {
+                        next = next.or(Some(letter));
+                    }
+                }
+            }
+        }
+        let next = next.or_else(|| {
+            self.pairs.iter().flat_map(|x| x.0.iter().chain(x.1.iter())).copied().find(unplaced)
+        });
+
+        let letter = match next {
+            Some(letter) => letter,
+            None => return solutions.push(board)
+        };
+        for row in 0..board.size() {
+            for column in 0..board.size() {
+                if board.get_element(row, column).is_none() {
+                    let mut child = board.clone();
+                    child.place(letter, [row, column]);
+                    self.search(child, limit, solutions);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::analysis::error::AnalysisError;
+    use crate::analysis::known_plaintext::KnownPlaintext;
+    use crate::playfair::alphabet::AlphabetPolicy;
+    use crate::playfair::error::PlayfairError;
+    use crate::playfair::play::PlayfairCypher;
+
+    #[test]
+    fn solutions_encrypt_the_crib() {
+        let cypher = PlayfairCypher::init("playfair example").unwrap();
+        let plaintext = "Hide the gold in the tree stump";
+        let known = KnownPlaintext::init(plaintext, &cypher.encrypt(plaintext).unwrap(), AlphabetPolicy::default()).unwrap();
+
+        let solutions = known.solve(20);
+        assert!(!solutions.is_empty());
+        for board in solutions {
+            assert_eq!(Some([0, 0]), board.get_position('H'));
+            for (plain, cipher) in known.pairs() {
+                let positions = plain.map(|x| board.get_position(x).unwrap());
+                assert_eq!(cipher.map(|x| board.get_position(x).unwrap()), board.encode(positions));
+            }
+        }
+    }
+
+    #[test]
+    fn long_crib_recovers_board() {
+        let cypher = PlayfairCypher::init("monarchy").unwrap();
+        let plaintext = "the quick brown fox jumps over the lazy dog while five boxing wizards jump quickly";
+        let known = KnownPlaintext::init(plaintext, &cypher.encrypt(plaintext).unwrap(), AlphabetPolicy::default()).unwrap();
+
+        let solutions = known.solve(2);
+        assert_eq!(1, solutions.len());
+        let board = solutions[0].to_board().unwrap();
+        let found = PlayfairCypher::from_board(board, AlphabetPolicy::default()).unwrap();
+        assert_eq!(cypher.encrypt("attack at dawn").unwrap(), found.encrypt("attack at dawn").unwrap());
+    }
+
+    #[test]
+    fn contradicting_pairs() {
+        let pairs = [(['A', 'B'], ['C', 'D']), (['A', 'B'], ['E', 'F'])];
+        let known = KnownPlaintext::from_pairs(&pairs, AlphabetPolicy::default()).unwrap();
+        assert!(known.solve(10).is_empty());
+    }
+
+    #[test]
+    fn invalid_pairs() {
+        let policy = AlphabetPolicy::default();
+        assert!(matches!(KnownPlaintext::from_pairs(&[(['A', 'B'], ['A', 'C'])], policy), Err(AnalysisError::InvalidCribPair { .. })));
+        assert!(matches!(KnownPlaintext::from_pairs(&[(['A', 'A'], ['B', 'C'])], policy), Err(AnalysisError::InvalidCribPair { .. })));
+        assert!(matches!(KnownPlaintext::from_pairs(&[(['A', 'J'], ['B', 'C'])], policy), Err(AnalysisError::Playfair(PlayfairError::SymbolNotOnBoard(_)))));
+        assert_eq!(Some(AnalysisError::CribLengthMismatch { plaintext: 2, ciphertext: 1 }), KnownPlaintext::init("abcd", "ef", policy).err());
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+use crate::playfair::alphabet::AlphabetPolicy;
+
+/// Index of coincidence above which a text looks like plain English rather than
+/// ciphertext. English is about 0.066, Playfair ciphertext usually stays below 0.055.
+const PLAINTEXT_IOC: f64 = 0.06;
+
+/// Statistics of a text, broken down into pairs the way Playfair ciphertext is.
+#[derive(Debug)]
+pub struct DigraphReport {
+    /// Number of symbols after normalizing with the alphabet policy.
+    pub symbols: usize,
+    /// Letters of the text the policy had to replace or drop, like `J` with the default policy.
+    pub outside_alphabet: usize,
+    /// Count of every pair, most frequent first.
+    pub digraphs: Vec<([char; 2], usize)>,
+    /// Number of pairs seen both ways round, like `AB` and `BA`.
+    pub reversed_pairs: usize,
+    /// Number of pairs of two identical letters.
+    pub doubled_pairs: usize,
+    /// Probability that two symbols drawn from the text are the same.
+    pub index_of_coincidence: f64,
+}
+
+impl DigraphReport {
+
+    /// Analyze the text with the given policy.
+    /// # Arguments
+    ///
+    /// - `text`: text to analyze, usually ciphertext.
+    /// - `policy`: alphabet policy the text is normalized with, like `PlayfairCypher::filter`.
+    pub fn init(text: &str, policy: AlphabetPolicy) -> Self {
+
+        let outside_alphabet = text.chars()
+            .filter(|x| x.is_ascii_alphabetic() && !policy.contains(x.to_ascii_uppercase()))
+            .count();
+        let symbols = policy.normalize(text);
+
+        let mut letters: HashMap<char, usize> = HashMap::new();
+        for symbol in &symbols {
+            *letters.entry(*symbol).or_default() += 1;
+        }
+        let n = symbols.len() as f64;
+        let index_of_coincidence = match symbols.len() {
+            0 | 1 => 0.0,
+            _ => letters.values().map(|&x| (x * (x - 1)) as f64).sum::<f64>() / (n * (n - 1.0))
+        };
+
+        let mut counts: HashMap<[char; 2], usize> = HashMap::new();
+        for pair in symbols.chunks_exact(2) {
+            *counts.entry([pair[0], pair[1]]).or_default() += 1;
+        }
+        let reversed_pairs = counts.keys()
+            .filter(|x| x[0] < x[1] && counts.contains_key(&[x[1], x[0]]))
+            .count();
+        let doubled_pairs = counts.iter().filter(|x| x.0[0] == x.0[1]).map(|x| x.1).sum();
+
+        let mut digraphs: Vec<([char; 2], usize)> = counts.into_iter().collect();
+        digraphs.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        DigraphReport {
+            symbols: symbols.len(),
+            outside_alphabet,
+            digraphs,
+            reversed_pairs,
+            doubled_pairs,
+            index_of_coincidence,
+        }
+    }
+
+    /// Returns `True` if the text could be Playfair ciphertext: an even number of symbols,
+    /// no letters outside the alphabet, no pair of identical letters, and letter frequencies
+    /// flatter than English.
+    pub fn likely_playfair(&self) -> bool {
+        self.symbols > 0
+            && self.symbols.is_multiple_of(2)
+            && self.outside_alphabet == 0
+            && self.doubled_pairs == 0
+            && self.index_of_coincidence < PLAINTEXT_IOC
+    }
+}
+
+impl Display for DigraphReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "symbols: {}", self.symbols)?;
+        writeln!(f, "outside alphabet: {}", self.outside_alphabet)?;
+        writeln!(f, "index of coincidence: {:.4}", self.index_of_coincidence)?;
+        writeln!(f, "reversed pairs: {}", self.reversed_pairs)?;
+        writeln!(f, "doubled pairs: {}", self.doubled_pairs)?;
+        writeln!(f, "likely playfair: {}", if self.likely_playfair() { "yes" } else { "no" })?;
+        for (pair, count) in self.digraphs.iter().take(10) {
+            writeln!(f, "{}{} {}", pair[0], pair[1], count)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::analysis::stats::DigraphReport;
+    use crate::playfair::alphabet::AlphabetPolicy;
+    use crate::playfair::play::PlayfairCypher;
+
+    const TEXT: &str = "It was the best of times, it was the worst of times, it was the age of wisdom, \
+        it was the age of foolishness, it was the epoch of belief, it was the epoch of incredulity, \
+        it was the season of light, it was the season of darkness.";
+
+    #[test]
+    fn counts() {
+        let report = DigraphReport::init("abba abab jj", AlphabetPolicy::default());
+        assert_eq!(10, report.symbols);
+        assert_eq!(2, report.outside_alphabet);
+        assert_eq!((['A', 'B'], 3), report.digraphs[0]);
+        assert_eq!(1, report.reversed_pairs);
+        assert_eq!(1, report.doubled_pairs);
+        assert!((report.index_of_coincidence - 26.0 / 90.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ciphertext_is_likely_playfair() {
+        let ciphertext = PlayfairCypher::init("monarchy").unwrap().encrypt(TEXT).unwrap();
+        assert!(DigraphReport::init(&ciphertext, AlphabetPolicy::default()).likely_playfair());
+    }
+
+    #[test]
+    fn plaintext_is_not_playfair() {
+        assert!(!DigraphReport::init(TEXT, AlphabetPolicy::default()).likely_playfair());
+        assert!(!DigraphReport::init("ABC", AlphabetPolicy::default()).likely_playfair());
+    }
+}
@@ -22,8 +22,7 @@ pub fn main() {
  */
 fn hex_from_string_slice(msg: &str) -> String {
 
-    let msg = encode(msg).to_owned();
-    msg
+    encode(msg)
 }
 
 /**
@@ -51,7 +50,7 @@ fn binary_u8(num: u8) -> String {
             0 => s.push('0'),
             _ => s.push('1')
         }
-        cnt = cnt - 1;
+        cnt -= 1;
     }
     s
 }
\ No newline at end of file
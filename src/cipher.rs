@@ -0,0 +1,118 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use crate::playfair::error::PlayfairError;
+#[cfg(feature = "serde")]
+use crate::playfair::{four_square::FourSquareCipher, play::PlayfairCypher, two_square::TwoSquareCipher};
+
+/// Common interface of every cipher in the crate, so tooling can be written once
+/// and switch between algorithms, e.g. behind a `Box<dyn Cipher>`.
+pub trait Cipher: Send + Sync {
+
+    /// Returns the message encrypted.
+    fn encrypt(&self, message: &str) -> Result<String, PlayfairError>;
+
+    /// Returns the ciphertext decrypted.
+    fn decrypt(&self, ciphertext: &str) -> Result<String, PlayfairError>;
+
+    /// Returns the symbols the cipher operates on, in order.
+    fn alphabet(&self) -> Vec<char>;
+
+    /// Returns the text reduced to the alphabet of the cipher, the way it is
+    /// seen before encryption.
+    fn normalize(&self, text: &str) -> String;
+}
+
+/// Any cipher of the crate with its exact configuration, tagged with its `variant`, so a
+/// configuration can be saved and loaded without knowing the cipher up front.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "variant", rename_all = "snake_case")]
+pub enum CipherConfig {
+    Playfair(PlayfairCypher),
+    TwoSquare(TwoSquareCipher),
+    FourSquare(FourSquareCipher),
+}
+
+#[cfg(feature = "serde")]
+impl CipherConfig {
+
+    /// Returns the configured cipher.
+    pub fn into_cipher(self) -> Box<dyn Cipher> {
+        match self {
+            CipherConfig::Playfair(cipher) => Box::new(cipher),
+            CipherConfig::TwoSquare(cipher) => Box::new(cipher),
+            CipherConfig::FourSquare(cipher) => Box::new(cipher),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::cipher::Cipher;
+    use crate::playfair::four_square::FourSquareCipher;
+    use crate::playfair::play::PlayfairCypher;
+    use crate::playfair::two_square::{TwoSquareCipher, TwoSquareLayout};
+
+    #[test]
+    fn round_trip_all_ciphers() {
+        let ciphers: Vec<Box<dyn Cipher>> = vec![
+            Box::new(PlayfairCypher::init("Playfair example").unwrap()),
+            Box::new(TwoSquareCipher::init("example", "keyword", TwoSquareLayout::VERTICAL).unwrap()),
+            Box::new(FourSquareCipher::init("example", "keyword").unwrap()),
+        ];
+
+        for cipher in ciphers {
+            let normalized = cipher.normalize("Jolly good show!");
+            assert_eq!("IOLLYGOODSHOW", normalized);
+            assert_eq!(25, cipher.alphabet().len());
+
+            let encrypted = cipher.encrypt(&normalized).unwrap();
+            assert_eq!("IOLXLYGOODSHOW", cipher.decrypt(&encrypted).unwrap());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn config_round_trip() {
+        use crate::cipher::CipherConfig;
+        use crate::playfair::alphabet::AlphabetPolicy;
+
+        let mut playfair = PlayfairCypher::init_with_policy("Playfair example", AlphabetPolicy::DropQ).unwrap();
+        playfair.set_filler('Z', 'K').unwrap();
+        playfair.set_strip_filler(true);
+        let configs = vec![
+            CipherConfig::Playfair(playfair),
+            CipherConfig::TwoSquare(TwoSquareCipher::init("example", "keyword", TwoSquareLayout::VERTICAL).unwrap()),
+            CipherConfig::FourSquare(FourSquareCipher::init("example", "keyword").unwrap()),
+        ];
+
+        for config in configs {
+            let json = serde_json::to_string(&config).unwrap();
+            let toml = toml::to_string(&config).unwrap();
+            let expected = config.into_cipher().encrypt("Jolly good show").unwrap();
+
+            let from_json: CipherConfig = serde_json::from_str(&json).unwrap();
+            let from_toml: CipherConfig = toml::from_str(&toml).unwrap();
+            assert_eq!(expected, from_json.into_cipher().encrypt("Jolly good show").unwrap());
+            assert_eq!(expected, from_toml.into_cipher().encrypt("Jolly good show").unwrap());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn config_is_checked() {
+        use crate::cipher::CipherConfig;
+
+        let json = serde_json::to_string(&CipherConfig::Playfair(PlayfairCypher::init("secret").unwrap())).unwrap();
+        assert!(json.starts_with(r#"{"variant":"playfair","board":[["S","E","C","R","T"],"#));
+        assert!(json.ends_with(r#""digraphs":{"policy":"MergeJI","filler":"X","filler_fallback":"Q","strip_filler":false},"preserve_format":false}"#));
+
+        let duplicate = json.replacen(r#"["S","E""#, r#"["S","S""#, 1);
+        assert!(serde_json::from_str::<CipherConfig>(&duplicate).err().unwrap().to_string().contains("symbol appears more than once: 'S'"));
+        let filler = json.replace(r#""filler":"X""#, r#""filler":"J""#);
+        assert!(serde_json::from_str::<CipherConfig>(&filler).err().unwrap().to_string().contains("symbol is not on the board: 'J'"));
+        let policy = json.replace("MergeJI", "MergeIJ");
+        assert!(serde_json::from_str::<CipherConfig>(&policy).is_err());
+    }
+}
@@ -0,0 +1,266 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+
+use rand::seq::SliceRandom;
+
+use crate::playfair::alphabet::AlphabetPolicy;
+use crate::playfair::error::PlayfairError;
+use crate::playfair::play::PlayfairCypher;
+
+const USAGE: &str = "\
+Usage: playfair-cypher <command> [options]
+
+Commands:
+    encrypt     Encrypt text with a key phrase
+    decrypt     Decrypt text with a key phrase
+    board       Show the board for a key phrase
+    keygen      Generate a random key
+
+Options:
+    -k, --key <phrase>      Secret key phrase
+    -t, --text <text>       Text to process, instead of --input
+    -i, --input <file>      Read text from file, stdin if neither --text nor --input is given
+    -o, --output <file>     Write result to file, stdout by default
+    -p, --policy <policy>   merge-ji (default), merge-ij, drop-q, merge-vu, split-w or alphanumeric
+    -s, --strip-filler      Remove fillers when decrypting
+    -h, --help              Show this message";
+
+#[derive(PartialEq, Debug)]
+enum Command {
+    Encrypt,
+    Decrypt,
+    Board,
+    Keygen,
+    Help
+}
+
+/// Parsed command line arguments.
+#[derive(PartialEq, Debug)]
+struct Options {
+    command: Command,
+    key: Option<String>,
+    text: Option<String>,
+    input: Option<String>,
+    output: Option<String>,
+    policy: AlphabetPolicy,
+    strip_filler: bool,
+}
+
+/// Errors of the command line interface, each with its own exit code.
+#[derive(Debug)]
+enum CliError {
+    Usage(String),
+    Cipher(PlayfairError),
+    Io(io::Error),
+}
+
+impl CliError {
+    fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Usage(_) => 2,
+            CliError::Cipher(_) | CliError::Io(_) => 1,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(msg) => write!(f, "{}\n\n{}", msg, USAGE),
+            CliError::Cipher(err) => write!(f, "{}", err),
+            CliError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl From<PlayfairError> for CliError {
+    fn from(err: PlayfairError) -> Self {
+        CliError::Cipher(err)
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(err: io::Error) -> Self {
+        CliError::Io(err)
+    }
+}
+
+/// Run the command line interface with the given arguments, without the program name.
+/// Returns the exit code, `0` on success, `1` on cipher or io errors and `2` on usage errors.
+pub fn run(args: &[String]) -> i32 {
+    match parse(args).and_then(|options| execute(&options)) {
+        Ok(_) => 0,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            err.exit_code()
+        }
+    }
+}
+
+/// Returns the alphabet policy for the given name.
+fn parse_policy(name: &str) -> Result<AlphabetPolicy, CliError> {
+    match name {
+        "merge-ji" => Ok(AlphabetPolicy::MergeJI),
+        "merge-ij" => Ok(AlphabetPolicy::MergeIJ),
+        "drop-q" => Ok(AlphabetPolicy::DropQ),
+        "merge-vu" => Ok(AlphabetPolicy::MergeVU),
+        "split-w" => Ok(AlphabetPolicy::SplitW),
+        "alphanumeric" => Ok(AlphabetPolicy::Alphanumeric),
+        _ => Err(CliError::Usage(format!("unknown policy '{}'", name)))
+    }
+}
+
+/// Returns the options parsed from the arguments.
+fn parse(args: &[String]) -> Result<Options, CliError> {
+
+    let command = match args.first().map(|x| x.as_str()) {
+        Some("encrypt") => Command::Encrypt,
+        Some("decrypt") => Command::Decrypt,
+        Some("board") => Command::Board,
+        Some("keygen") => Command::Keygen,
+        Some("-h") | Some("--help") | Some("help") => Command::Help,
+        Some(other) => return Err(CliError::Usage(format!("unknown command '{}'", other))),
+        None => return Err(CliError::Usage(String::from("missing command")))
+    };
+
+    let mut options = Options {
+        command,
+        key: None,
+        text: None,
+        input: None,
+        output: None,
+        policy: AlphabetPolicy::default(),
+        strip_filler: false,
+    };
+
+    let mut args = args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next().cloned().ok_or_else(|| CliError::Usage(format!("missing value for '{}'", arg)))
+        };
+        match arg.as_str() {
+            "-k" | "--key" => options.key = Some(value()?),
+            "-t" | "--text" => options.text = Some(value()?),
+            "-i" | "--input" => options.input = Some(value()?),
+            "-o" | "--output" => options.output = Some(value()?),
+            "-p" | "--policy" => options.policy = parse_policy(&value()?)?,
+            "-s" | "--strip-filler" => options.strip_filler = true,
+            "-h" | "--help" => options.command = Command::Help,
+            _ => return Err(CliError::Usage(format!("unknown option '{}'", arg)))
+        }
+    }
+
+    if options.text.is_some() && options.input.is_some() {
+        return Err(CliError::Usage(String::from("use either --text or --input")));
+    }
+    Ok(options)
+}
+
+/// Returns the cypher for the key of the options.
+fn cypher(options: &Options) -> Result<PlayfairCypher, CliError> {
+    let key = options.key.as_ref().ok_or_else(|| CliError::Usage(String::from("missing --key")))?;
+    let mut cypher = PlayfairCypher::init_with_policy(key, options.policy)?;
+    cypher.set_strip_filler(options.strip_filler);
+    Ok(cypher)
+}
+
+/// Returns the reader for the text to process, from `--text`, `--input` or stdin.
+fn input(options: &Options) -> Result<Box<dyn Read>, CliError> {
+    if let Some(text) = &options.text {
+        return Ok(Box::new(io::Cursor::new(text.clone().into_bytes())));
+    }
+    match &options.input {
+        Some(path) => Ok(Box::new(BufReader::new(File::open(path)?))),
+        None => Ok(Box::new(io::stdin()))
+    }
+}
+
+/// Returns the writer for the result, `--output` or stdout.
+fn output(options: &Options) -> Result<Box<dyn Write>, CliError> {
+    match &options.output {
+        Some(path) => Ok(Box::new(BufWriter::new(File::create(path)?))),
+        None => Ok(Box::new(io::stdout()))
+    }
+}
+
+/// Returns a random key, the shuffled alphabet of the policy in rows.
+fn keygen(policy: AlphabetPolicy) -> String {
+    let mut symbols = policy.symbols();
+    symbols.shuffle(&mut rand::thread_rng());
+
+    symbols.chunks(policy.size())
+        .map(|x| x.iter().collect::<String>())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// End the output with a newline and flush it.
+fn finish(mut writer: Box<dyn Write>) -> Result<(), CliError> {
+    writeln!(writer)?;
+    Ok(writer.flush()?)
+}
+
+/// Write the result to `--output` or stdout.
+fn write_result(options: &Options, result: &str) -> Result<(), CliError> {
+    let mut writer = output(options)?;
+    write!(writer, "{}", result)?;
+    finish(writer)
+}
+
+fn execute(options: &Options) -> Result<(), CliError> {
+    match options.command {
+        Command::Encrypt => {
+            let (cypher, reader) = (cypher(options)?, input(options)?);
+            let mut writer = output(options)?;
+            cypher.encrypt_stream(reader, &mut writer)?;
+            finish(writer)
+        },
+        Command::Decrypt => {
+            let (cypher, reader) = (cypher(options)?, input(options)?);
+            let mut writer = output(options)?;
+            cypher.decrypt_stream(reader, &mut writer)?;
+            finish(writer)
+        },
+        Command::Board => write_result(options, cypher(options)?.board().to_string().trim_end()),
+        Command::Keygen => write_result(options, &keygen(options.policy)),
+        Command::Help => write_result(options, USAGE),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::cli::{keygen, parse, CliError, Command};
+    use crate::playfair::alphabet::AlphabetPolicy;
+    use crate::playfair::play::PlayfairCypher;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split(' ').map(String::from).collect()
+    }
+
+    #[test]
+    fn parse_encrypt() {
+        let options = parse(&args("encrypt --key secret -t hello -p drop-q -s")).unwrap();
+        assert_eq!(Command::Encrypt, options.command);
+        assert_eq!(Some(String::from("secret")), options.key);
+        assert_eq!(Some(String::from("hello")), options.text);
+        assert_eq!(AlphabetPolicy::DropQ, options.policy);
+        assert!(options.strip_filler);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(matches!(parse(&args("shuffle")), Err(CliError::Usage(_))));
+        assert!(matches!(parse(&args("encrypt --key")), Err(CliError::Usage(_))));
+        assert!(matches!(parse(&args("encrypt -p klingon")), Err(CliError::Usage(_))));
+        assert!(matches!(parse(&args("encrypt -t a -i b")), Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn keygen_is_valid_key() {
+        let key = keygen(AlphabetPolicy::Alphanumeric);
+        assert_eq!(6, key.split(' ').count());
+        assert!(PlayfairCypher::init_with_policy(&key, AlphabetPolicy::Alphanumeric).is_ok());
+    }
+}
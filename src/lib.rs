@@ -0,0 +1,7 @@
+pub mod hash;
+pub mod binary;
+pub mod playfair;
+pub mod encryption;
+pub mod cipher;
+pub mod cli;
+pub mod analysis;
@@ -1,4 +1,15 @@
 pub mod container;
 pub mod array;
 pub mod utils;
+pub mod alphabet;
+pub mod error;
+pub mod digraph;
+pub mod fill;
+pub mod format;
+pub mod two_square;
+pub mod four_square;
+pub mod stream;
+pub mod trace;
+pub mod render;
+pub mod export;
 pub mod play;
\ No newline at end of file
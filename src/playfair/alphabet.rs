@@ -0,0 +1,121 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// How the 26 letters of the english alphabet are reduced to the 25 symbols of a 5x5 board,
+/// or extended with digits to the 36 symbols of a 6x6 board.
+/// The same policy is applied to the key phrase and to the message.
+/// https://en.wikipedia.org/wiki/Playfair_cipher
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum AlphabetPolicy {
+    /// Replace every `J` with `I`.
+    #[default]
+    MergeJI,
+    /// Replace every `I` with `J`.
+    MergeIJ,
+    /// Drop every `Q`.
+    DropQ,
+    /// Replace every `V` with `U`.
+    MergeVU,
+    /// Replace every `W` with `VV`.
+    SplitW,
+    /// Keep all letters and the digits `0` to `9`, on a 6x6 board.
+    Alphanumeric,
+}
+
+const LETTERS: &[u8; 26] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &[u8; 10] = b"0123456789";
+
+impl AlphabetPolicy {
+
+    /// Returns the letter that is not part of the alphabet under this policy, if any.
+    fn removed(&self) -> Option<char> {
+        match self {
+            AlphabetPolicy::MergeJI => Some('J'),
+            AlphabetPolicy::MergeIJ => Some('I'),
+            AlphabetPolicy::DropQ => Some('Q'),
+            AlphabetPolicy::MergeVU => Some('V'),
+            AlphabetPolicy::SplitW => Some('W'),
+            AlphabetPolicy::Alphanumeric => None,
+        }
+    }
+
+    /// Returns the number of rows and columns of a board holding the alphabet.
+    pub fn size(&self) -> usize {
+        match self {
+            AlphabetPolicy::Alphanumeric => 6,
+            _ => 5
+        }
+    }
+
+    /// Returns the symbols of the alphabet, in order.
+    pub fn symbols(&self) -> Vec<char> {
+        LETTERS.iter()
+            .chain(DIGITS.iter())
+            .map(|&x| x as char)
+            .filter(|&x| self.contains(x))
+            .collect()
+    }
+
+    /// Returns `True` if the symbol is part of the alphabet.
+    pub fn contains(&self, symbol: char) -> bool {
+        match self {
+            AlphabetPolicy::Alphanumeric => symbol.is_ascii_uppercase() || symbol.is_ascii_digit(),
+            _ => symbol.is_ascii_uppercase() && Some(symbol) != self.removed()
+        }
+    }
+
+    /// Returns the symbols the given character is replaced with. The result is empty
+    /// if the character has no place in the alphabet.
+    pub fn map(&self, letter: char) -> Vec<char> {
+        let letter = letter.to_ascii_uppercase();
+
+        match (self, letter) {
+            (AlphabetPolicy::MergeJI, 'J') => vec!['I'],
+            (AlphabetPolicy::MergeIJ, 'I') => vec!['J'],
+            (AlphabetPolicy::DropQ, 'Q') => vec![],
+            (AlphabetPolicy::MergeVU, 'V') => vec!['U'],
+            (AlphabetPolicy::SplitW, 'W') => vec!['V', 'V'],
+            _ if self.contains(letter) => vec![letter],
+            _ => vec![]
+        }
+    }
+
+    /// Returns the message reduced to the symbols of the alphabet. Everything
+    /// else is removed.
+    pub fn normalize(&self, msg: &str) -> Vec<char> {
+        msg.chars().flat_map(|x| self.map(x)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::playfair::alphabet::AlphabetPolicy;
+
+    #[test]
+    fn normalize_merge_ji() {
+        let policy = AlphabetPolicy::MergeJI;
+        assert_eq!("IUMPIACK", policy.normalize("Jump, Jack!").iter().collect::<String>());
+    }
+
+    #[test]
+    fn normalize_split_w() {
+        let policy = AlphabetPolicy::SplitW;
+        assert_eq!("VVAVE", policy.normalize("wave").iter().collect::<String>());
+    }
+
+    #[test]
+    fn symbols_have_board_size() {
+        for policy in [AlphabetPolicy::MergeJI, AlphabetPolicy::MergeIJ, AlphabetPolicy::DropQ,
+                       AlphabetPolicy::MergeVU, AlphabetPolicy::SplitW, AlphabetPolicy::Alphanumeric] {
+            assert_eq!(policy.size() * policy.size(), policy.symbols().len());
+        }
+    }
+
+    #[test]
+    fn normalize_alphanumeric() {
+        let policy = AlphabetPolicy::Alphanumeric;
+        assert_eq!("JUNE1ST2024", policy.normalize("June 1st, 2024").iter().collect::<String>());
+    }
+}
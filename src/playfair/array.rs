@@ -1,87 +1,235 @@
 use std::fmt::{self, Display, Debug};
 use std::cmp::PartialEq;
+use std::collections::HashMap;
+use std::hash::Hash;
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use super::alphabet::AlphabetPolicy;
+use super::error::PlayfairError;
+use super::fill::FillPattern;
 
-#[derive(PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum BoardShape {
     ROW,
     COLUMN,
     RECTANGLE
 }
 
-pub struct Board<T> where T: Display + PartialEq {
-    pub state: [[T; 5]; 5]
+impl BoardShape {
+    /// Return the shape for the given positions, as `[row, column]`.
+    pub fn from_positions(p1: [usize; 2], p2: [usize; 2]) -> Self {
+
+        if p1[1] == p2[1] { BoardShape::COLUMN }
+        else if p1[0] == p2[0] { BoardShape::ROW }
+        else { BoardShape::RECTANGLE }
+    }
 }
 
-impl<T> Board<T> where T: Copy + Display + PartialEq {
-    pub fn init(data: &[T]) -> Self {
+pub struct Board<T> where T: Display + Eq + Hash {
+    state: Vec<Vec<T>>,
+    index: HashMap<T, [usize; 2]>
+}
 
-        let mut state = [[data[0]; 5]; 5];
+impl<T> Board<T> where T: Copy + Display + Eq + Hash {
+    /// Initialize a board with `rows` and `columns` from the elements of `data`, row by row.
+    /// Every element must be unique.
+    /// # Arguments
+    ///
+    /// - `data`: elements of the board, exactly `rows * columns` of them.
+    /// - `rows`: number of rows.
+    /// - `columns`: number of columns.
+    pub fn init(data: &[T], rows: usize, columns: usize) -> Result<Self, PlayfairError> {
 
-        for i in 0..state.len() {
-            for j in 0..state[0].len() {
-                state[i][j] = data[i * 5 + j];
+        if rows == 0 || columns == 0 || data.len() != rows * columns {
+            return Err(PlayfairError::InvalidBoardSize(rows * columns, data.len()));
+        }
+        let mut index = HashMap::new();
+        for (i, element) in data.iter().enumerate() {
+            if index.insert(*element, [i / columns, i % columns]).is_some() {
+                return Err(PlayfairError::DuplicateSymbol(element.to_string()));
             }
         }
 
-        Board { state }
+        let state = data.chunks(columns).map(|x| x.to_vec()).collect();
+
+        Ok(Board { state, index })
+    }
+
+    /// Returns the number of rows.
+    pub fn rows(&self) -> usize {
+        self.state.len()
+    }
+
+    /// Returns the number of columns.
+    pub fn columns(&self) -> usize {
+        self.state[0].len()
+    }
+
+    /// Returns the element at the given row and column. Panics if either is out of range,
+    /// callers only pass positions taken from the board or bounded by its size.
+    pub(crate) fn get_element(&self, row: usize, column: usize) -> T {
+        self.state[row][column]
     }
 
     /// Returns an array. The array contains the position of
     /// the specified element if it exists, as `[row, column]`.
-    pub fn get_position(&self, element: T) -> Option<[usize; 2]> {
+    pub fn get_position(&self, element: T) -> Result<[usize; 2], PlayfairError> {
+
+        match self.index.get(&element) {
+            Some(position) => Ok(*position),
+            None => Err(PlayfairError::SymbolNotOnBoard(element.to_string()))
+        }
+    }
+
+    /// Return the shape for the given pair, can be either 
+    /// `row`, `column` or `rectangle`.
+    pub fn get_shape(&self, pair: [T; 2]) -> Result<BoardShape, PlayfairError> {
+
+        let p1 = self.get_position(pair[0])?;
+        let p2 = self.get_position(pair[1])?;
+        Ok(BoardShape::from_positions(p1, p2))
+    }
+}
+
+impl Board<char> {
+    /// Initialize a square board from a secret phrase, followed by the rest of the alphabet.
+    /// Repeated letters of the phrase are only placed once. The board is 5x5, or
+    /// 6x6 for `AlphabetPolicy::Alphanumeric`.
+    /// # Arguments
+    ///
+    /// - `phrase`: secret phrase, spaces are ignored.
+    /// - `policy`: how the alphabet is mapped onto the board.
+    pub fn keyed(phrase: &str, policy: AlphabetPolicy) -> Result<Self, PlayfairError> {
+        Self::keyed_with_pattern(phrase, policy, FillPattern::default())
+    }
+
+    /// Initialize a square board from a secret phrase, followed by the rest of the alphabet,
+    /// written into the board in the order of the fill pattern.
+    /// # Arguments
+    ///
+    /// - `phrase`: secret phrase, spaces are ignored.
+    /// - `policy`: how the alphabet is mapped onto the board.
+    /// - `pattern`: order in which the cells are filled.
+    pub fn keyed_with_pattern(phrase: &str, policy: AlphabetPolicy, pattern: FillPattern) -> Result<Self, PlayfairError> {
+
+        let mut data: Vec<char> = vec![];
+        let phrase = phrase.replace(" ", "");
+
+        if let Some(letter) = phrase.chars().find(|&x| !x.is_ascii_alphabetic() && !policy.contains(x)) {
+            return Err(PlayfairError::InvalidKeyCharacter(letter));
+        }
+
+        let mut keyword = 0;
+        for letter in policy.normalize(&phrase) {
+            if !data.contains(&letter) {
+                data.push(letter);
+                keyword += 1;
+            }
+        }
+        for letter in policy.symbols() {
+            if !data.contains(&letter) {
+                data.push(letter);
+            }
+        }
+
+        let size = policy.size();
+        let mut grid = data.clone();
+        for (letter, [row, column]) in pattern.arrange(data, keyword).into_iter().zip(pattern.positions(size)) {
+            grid[row * size + column] = letter;
+        }
+        Board::init(&grid, size, size)
+    }
 
-        for i in 0..self.state[0].len() {
-            for j in 0..self.state[1].len() {
-                if element == self.state[i][j] {
-                    return Some([i, j]);
-                }
+    /// Initialize a square board from an explicit grid, like `"PLAYF IREXM BCDGH KNOQS TUVWZ"`.
+    /// Case is ignored, and so is whitespace between the rows. Every symbol of the
+    /// alphabet must appear exactly once.
+    /// # Arguments
+    ///
+    /// - `grid`: symbols of the board, row by row.
+    /// - `policy`: alphabet the board holds.
+    pub fn from_grid(grid: &str, policy: AlphabetPolicy) -> Result<Self, PlayfairError> {
+
+        let size = policy.size();
+        let rows: Vec<&str> = grid.split_whitespace().collect();
+        if rows.len() > 1 {
+            if let Some((row, symbols)) = rows.iter().enumerate().find(|x| x.1.chars().count() != size) {
+                return Err(PlayfairError::InvalidRowLength { row: row + 1, expected: size, found: symbols.chars().count() });
             }
         }
-        None
+
+        let data: Vec<char> = rows.concat().chars().map(|x| x.to_ascii_uppercase()).collect();
+        if let Some(symbol) = data.iter().find(|x| !policy.contains(**x)) {
+            return Err(PlayfairError::SymbolNotInAlphabet(*symbol));
+        }
+        if let Some((_, symbol)) = data.iter().enumerate().find(|(i, x)| data[..*i].contains(x)) {
+            return Err(PlayfairError::DuplicateSymbol(symbol.to_string()));
+        }
+        let missing: String = policy.symbols().into_iter().filter(|x| !data.contains(x)).collect();
+        if !missing.is_empty() {
+            return Err(PlayfairError::MissingSymbols(missing));
+        }
+
+        Board::init(&data, size, size)
     }
 
-    /// Returns `True` if the pair shares the same column.
-    fn is_shape_column(&self, pair: [T; 2]) -> bool {
-        let c1 = self.get_position(pair[0]).unwrap()[1];
-        let c2 = self.get_position(pair[1]).unwrap()[1];
+    /// Returns an error unless every symbol on the board is part of the alphabet of the
+    /// policy, and every letter of the alphabet is on the board. The board may have any
+    /// shape, a 4x8 board holds the letters and six of the digits of the alphanumeric alphabet.
+    pub fn check_alphabet(&self, policy: AlphabetPolicy) -> Result<(), PlayfairError> {
 
-        if c1 == c2 { return true; }
-        false
+        if let Some(symbol) = self.state.iter().flatten().find(|x| !policy.contains(**x)) {
+            return Err(PlayfairError::SymbolNotInAlphabet(*symbol));
+        }
+        let missing: String = policy.symbols().into_iter()
+            .filter(|x| x.is_ascii_alphabetic() && !self.index.contains_key(x))
+            .collect();
+        if !missing.is_empty() {
+            return Err(PlayfairError::MissingSymbols(missing));
+        }
+        Ok(())
     }
 
-    /// Returns `True` if the pair shares the same row.
-    fn is_shape_row(&self, pair: [T; 2]) -> bool {
-        let r1 = self.get_position(pair[0]).unwrap()[0];
-        let r2 = self.get_position(pair[1]).unwrap()[0];
+    /// Returns an error unless the board is square, sized for the policy, and holds
+    /// every symbol of its alphabet, as the two-square and four-square ciphers need.
+    pub fn check_square(&self, policy: AlphabetPolicy) -> Result<(), PlayfairError> {
 
-        if r1 == r2 { return true; }
-        false
+        let size = policy.size();
+        if self.rows() != size || self.columns() != size {
+            return Err(PlayfairError::InvalidBoardSize(size * size, self.rows() * self.columns()));
+        }
+        self.check_alphabet(policy)
     }
+}
 
-    /// Returns `True` if the pair shapes a rectangle, i.e. they
-    /// are neither in the same row nor column.
-    #[allow(dead_code)]
-    fn is_shape_rectangle(&self, pair: [T; 2]) -> bool {
-        !self.is_shape_column(pair) && !self.is_shape_row(pair)
+/// Serialized as the grid of rows, the position index is rebuilt on load.
+#[cfg(feature = "serde")]
+impl<T> Serialize for Board<T> where T: Display + Eq + Hash + Serialize {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.state.serialize(serializer)
     }
+}
 
-    /// Return the shape for the given pair, can be either 
-    /// `row`, `column` or `rectangle`.
-    pub fn get_shape(&self, pair: [T; 2]) -> BoardShape {
+#[cfg(feature = "serde")]
+impl<'de, T> Deserialize<'de> for Board<T> where T: Copy + Display + Eq + Hash + Deserialize<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let state: Vec<Vec<T>> = Vec::deserialize(deserializer)?;
+        let columns = state.first().map_or(0, |x| x.len());
 
-        if self.is_shape_column(pair) { BoardShape::COLUMN }
-        else if self.is_shape_row(pair) { BoardShape::ROW }
-        else { BoardShape::RECTANGLE }
+        if let Some(row) = state.iter().find(|x| x.len() != columns) {
+            return Err(de::Error::custom(PlayfairError::InvalidBoardSize(columns, row.len())));
+        }
+        Board::init(&state.concat(), state.len(), columns).map_err(de::Error::custom)
     }
 }
 
-impl<T> fmt::Display for Board<T> where T: Display + PartialEq {
+impl<T> fmt::Display for Board<T> where T: Display + Eq + Hash {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for i in 0..self.state[0].len() {
-            for j in 0..self.state[1].len() {
+        for i in 0..self.state.len() {
+            for j in 0..self.state[i].len() {
                 write!(f, "{} ", self.state[i][j])?;
             }
-            write!(f, "\n")?;
+            writeln!(f)?;
         }
         Ok(())
     }
@@ -91,6 +239,7 @@ impl<T> fmt::Display for Board<T> where T: Display + PartialEq {
 mod tests {
 
     use crate::playfair::array::{Board, BoardShape};
+    use crate::playfair::error::PlayfairError;
     use crate::playfair::utils::convert_to_char;
 
     const CHARS: &[u8; 25] = b"ABCDEFGHIJKLMNOPQRSTUVXYZ";
@@ -98,29 +247,71 @@ mod tests {
     #[test]
     fn is_shape_rectangle() {
         let chars = convert_to_char(CHARS);
-        let board = Board::init(&chars);
-        assert_eq!(BoardShape::RECTANGLE, board.get_shape(['A', 'R']));
+        let board = Board::init(&chars, 5, 5).unwrap();
+        assert_eq!(BoardShape::RECTANGLE, board.get_shape(['A', 'R']).unwrap());
     }
 
     #[test]
     fn is_shape_column() {
         let chars = convert_to_char(CHARS);
-        let board = Board::init(&chars);
-        assert_eq!(BoardShape::COLUMN, board.get_shape(['G', 'Q']));
+        let board = Board::init(&chars, 5, 5).unwrap();
+        assert_eq!(BoardShape::COLUMN, board.get_shape(['G', 'Q']).unwrap());
     }
 
     #[test]
     fn is_shape_row() {
         let chars = convert_to_char(CHARS);
-        let board = Board::init(&chars);
-        assert_eq!(BoardShape::ROW, board.get_shape(['L','O']));
+        let board = Board::init(&chars, 5, 5).unwrap();
+        assert_eq!(BoardShape::ROW, board.get_shape(['L','O']).unwrap());
     }
 
     #[test]
     fn get_position() {
         let chars = convert_to_char(CHARS);
-        let board = Board::init(&chars);
+        let board = Board::init(&chars, 5, 5).unwrap();
         assert_eq!([3, 2], board.get_position('R').unwrap());
     }
-}
 
+    #[test]
+    fn get_position_missing() {
+        let chars = convert_to_char(CHARS);
+        let board = Board::init(&chars, 5, 5).unwrap();
+        assert_eq!(Err(PlayfairError::SymbolNotOnBoard(String::from("W"))), board.get_position('W'));
+    }
+
+    #[test]
+    fn init_wrong_size() {
+        let chars = convert_to_char(CHARS);
+        assert_eq!(Err(PlayfairError::InvalidBoardSize(36, 25)), Board::init(&chars, 6, 6).map(|_| ()));
+    }
+
+    #[test]
+    fn get_shape_rectangular_board() {
+        let data: Vec<char> = "ABCDEFGHIJKLMNOPQRSTUVWXYZ012345".chars().collect();
+        let board = Board::init(&data, 4, 8).unwrap();
+        assert_eq!([1, 7], board.get_position('P').unwrap());
+        assert_eq!(BoardShape::ROW, board.get_shape(['I', 'P']).unwrap());
+        assert_eq!(BoardShape::COLUMN, board.get_shape(['B', 'Z']).unwrap());
+        assert_eq!(BoardShape::RECTANGLE, board.get_shape(['A', 'P']).unwrap());
+    }
+
+    #[test]
+    fn init_duplicate() {
+        let mut chars = convert_to_char(CHARS);
+        chars[24] = 'A';
+        assert!(matches!(Board::init(&chars, 5, 5), Err(PlayfairError::DuplicateSymbol(_))));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_grid() {
+        let board = Board::init(&[1, 2, 3, 4, 5, 6], 2, 3).unwrap();
+        let json = serde_json::to_string(&board).unwrap();
+        assert_eq!("[[1,2,3],[4,5,6]]", json);
+
+        let loaded: Board<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!([1, 2], loaded.get_position(6).unwrap());
+        assert!(serde_json::from_str::<Board<i32>>("[[1,2,3],[4,5]]").is_err());
+        assert!(serde_json::from_str::<Board<i32>>("[]").is_err());
+    }
+}
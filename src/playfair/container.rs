@@ -25,8 +25,8 @@ impl Playfair {
     fn init(text: &[u8], size: usize) -> Self {
 
         let mut data = Vec::new();
-        for x in 0..size*size {
-            data.push(text[x] as char);
+        for letter in text.iter().take(size*size) {
+            data.push(*letter as char);
         }
         Self { size, data }
     }
@@ -36,7 +36,7 @@ impl Playfair {
     fn convert(text: &str) -> Vec<LetterPair> {
 
         let mut text = text.replace(" ", "").to_uppercase();
-        if text.len() % 2 != 0 {
+        if !text.len().is_multiple_of(2) {
             text.push('X');
         }
 
@@ -93,12 +93,7 @@ impl Playfair {
     /// Return the row index of the given letter.
     fn get_row(&self, letter: char) -> Option<usize> {
         let index = self.get_index(letter).unwrap();
-        for row in 0..=self.size-1 {
-            if index <= self.size*row + self.size-1 {
-                return Some(row);
-            }
-        }
-        None
+        (0..self.size).find(|&row| index < self.size*row + self.size)
     }
 
     /// Returns `True` if two letters shape up a rectangle, i.e. they are neither
@@ -145,13 +140,12 @@ impl Playfair {
 
         for letter in letters {
             let row = self.get_row(letter).unwrap();
-            let char;
-            if row == self.size-1 {
-                char = self.get_letter(0, column);
+            let char = if row == self.size-1 {
+                self.get_letter(0, column)
             }
             else {
-                char = self.get_letter(row+1, column);
-            }
+                self.get_letter(row+1, column)
+            };
             encoded_letters.push(char);
         }
         encoded_letters
@@ -165,13 +159,12 @@ impl Playfair {
 
         for letter in letters {
             let column = self.get_column(letter).unwrap();
-            let char;
-            if column == self.size-1 {
-                char = self.get_letter(row, 0);
+            let char = if column == self.size-1 {
+                self.get_letter(row, 0)
             }
             else {
-                char = self.get_letter(row, column+1);
-            }
+                self.get_letter(row, column+1)
+            };
             encoded_letters.push(char);
         }
         encoded_letters 
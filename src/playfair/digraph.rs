@@ -0,0 +1,221 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use super::alphabet::AlphabetPolicy;
+use super::error::PlayfairError;
+
+/// Breaks text down into pairs of two and back again, the way every cipher of the
+/// Playfair family does. Holds the alphabet policy and the filler settings.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(try_from = "DigraphsConfig"))]
+pub struct Digraphs {
+    policy: AlphabetPolicy,
+    filler: char,
+    filler_fallback: char,
+    strip_filler: bool,
+}
+
+/// Serialized form of `Digraphs`, checked when loaded.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct DigraphsConfig {
+    policy: AlphabetPolicy,
+    filler: char,
+    filler_fallback: char,
+    strip_filler: bool,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<DigraphsConfig> for Digraphs {
+    type Error = PlayfairError;
+
+    fn try_from(config: DigraphsConfig) -> Result<Self, Self::Error> {
+        let mut digraphs = Digraphs::init(config.policy);
+        digraphs.set_filler(config.filler, config.filler_fallback)?;
+        digraphs.set_strip_filler(config.strip_filler);
+        Ok(digraphs)
+    }
+}
+
+impl Digraphs {
+
+    /// Initialize with the given policy, `X` as filler and `Q` as fallback, or `Z`
+    /// if the policy has no `Q`.
+    pub fn init(policy: AlphabetPolicy) -> Self {
+        Digraphs {
+            policy,
+            filler: 'X',
+            filler_fallback: if policy.contains('Q') { 'Q' } else { 'Z' },
+            strip_filler: false,
+        }
+    }
+
+    /// Returns the symbols of the alphabet, in order.
+    pub fn alphabet(&self) -> Vec<char> {
+        self.policy.symbols()
+    }
+
+    /// Returns the alphabet policy.
+    pub fn policy(&self) -> AlphabetPolicy {
+        self.policy
+    }
+
+    /// Set the filler inserted between identical letters of a pair, and used to pad
+    /// an uneven message. The `fallback` is used when the repeated letter is the filler itself.
+    /// # Arguments
+    ///
+    /// - `filler`: letter inserted between identical letters, `X` by default.
+    /// - `fallback`: letter used instead of `filler` when the repeated letter is `filler`, `Q` by default.
+    pub fn set_filler(&mut self, filler: char, fallback: char) -> Result<(), PlayfairError> {
+        let filler = filler.to_ascii_uppercase();
+        let fallback = fallback.to_ascii_uppercase();
+
+        if filler == fallback {
+            return Err(PlayfairError::FillerCollision(filler));
+        }
+        for symbol in [filler, fallback] {
+            if !self.policy.contains(symbol) {
+                return Err(PlayfairError::SymbolNotOnBoard(symbol.to_string()));
+            }
+        }
+        self.filler = filler;
+        self.filler_fallback = fallback;
+        Ok(())
+    }
+
+    /// Choose whether decoding removes the inserted fillers.
+    pub fn set_strip_filler(&mut self, strip: bool) {
+        self.strip_filler = strip;
+    }
+
+    /// Returns the filler to pair with the given letter.
+    fn filler_for(&self, letter: char) -> char {
+        if letter == self.filler { self.filler_fallback } else { self.filler }
+    }
+
+    /// Return a filtered array. Removing everything except the uppercase english alphabet,
+    /// reduced to 25 letters, or extended with digits, by the alphabet policy.
+    /// https://en.wikipedia.org/wiki/Playfair_cipher
+    pub fn filter(&self, msg: &str) -> Vec<char> {
+        self.policy.normalize(msg)
+    }
+
+    /// Filters a string slice down to the alphabet, and breaks it down into
+    /// pairs of two. A filler is inserted between identical letters of a pair,
+    /// and added to the last pair if uneven.
+    /// # Arguments
+    ///
+    /// - `message`: text message.
+    pub fn pairs(&self, message: &str) -> Result<Vec<[char; 2]>, PlayfairError> {
+
+        let mut pairs = self.pair_builder();
+        let mut data: Vec<[char; 2]> = self.filter(message).into_iter().filter_map(|x| pairs.push(x)).collect();
+        data.extend(pairs.finish());
+
+        if data.is_empty() {
+            return Err(PlayfairError::EmptyMessage);
+        }
+        Ok(data)
+    }
+
+    /// Returns the ciphertext broken down into pairs of two. No fillers are inserted,
+    /// the ciphertext must already consist of whole pairs.
+    pub fn cipher_pairs(&self, ciphertext: &str) -> Result<Vec<[char; 2]>, PlayfairError> {
+        let ciphertext = self.filter(ciphertext);
+
+        if ciphertext.is_empty() {
+            return Err(PlayfairError::EmptyMessage);
+        }
+        if !ciphertext.len().is_multiple_of(2) {
+            return Err(PlayfairError::OddLengthCiphertext);
+        }
+        Ok(ciphertext.chunks(2).map(|x| [x[0], x[1]]).collect())
+    }
+
+    /// Returns the decoded pairs as text. Fillers are removed if enabled with `set_strip_filler`.
+    pub fn text(&self, pairs: &[[char; 2]]) -> String {
+        let mut data = String::new();
+        let mut writer = self.text_writer();
+
+        for pair in pairs {
+            writer.push(*pair, &mut data);
+        }
+        writer.finish(&mut data);
+        data
+    }
+
+    /// Returns a builder that breaks a stream of symbols down into pairs, the way `pairs` does.
+    pub fn pair_builder(&self) -> PairBuilder<'_> {
+        PairBuilder { digraphs: self, pending: None }
+    }
+
+    /// Returns a writer that turns a stream of decoded pairs into text, the way `text` does.
+    pub fn text_writer(&self) -> TextWriter<'_> {
+        TextWriter { digraphs: self, pending: None }
+    }
+}
+
+/// Breaks a stream of symbols down into pairs of two, inserting fillers like `Digraphs::pairs`.
+/// A dangling letter is carried over until the next symbol arrives.
+pub struct PairBuilder<'a> {
+    digraphs: &'a Digraphs,
+    pending: Option<char>,
+}
+
+impl PairBuilder<'_> {
+
+    /// Add a symbol, returns a pair once one is complete.
+    pub fn push(&mut self, symbol: char) -> Option<[char; 2]> {
+        match self.pending.take() {
+            None => {
+                self.pending = Some(symbol);
+                None
+            },
+            Some(first) if first == symbol => {
+                self.pending = Some(symbol);
+                Some([first, self.digraphs.filler_for(first)])
+            },
+            Some(first) => Some([first, symbol])
+        }
+    }
+
+    /// Returns the last pair, padded with a filler, if a letter is left over.
+    pub fn finish(self) -> Option<[char; 2]> {
+        self.pending.map(|x| [x, self.digraphs.filler_for(x)])
+    }
+}
+
+/// Turns a stream of decoded pairs into text, like `Digraphs::text`. A filler is removed when
+/// it separates two identical letters, or pads the last pair, if enabled with `set_strip_filler`.
+/// The second letter of a pair is held back until the next pair shows whether it is a filler.
+pub struct TextWriter<'a> {
+    digraphs: &'a Digraphs,
+    pending: Option<[char; 2]>,
+}
+
+impl TextWriter<'_> {
+
+    /// Returns `True` if the pair ends with the filler for its first letter.
+    fn ends_with_filler(&self, pair: [char; 2]) -> bool {
+        self.digraphs.strip_filler && pair[1] == self.digraphs.filler_for(pair[0])
+    }
+
+    /// Add a decoded pair, appending the text that is settled to `data`.
+    pub fn push(&mut self, pair: [char; 2], data: &mut String) {
+        if let Some(previous) = self.pending.replace(pair) {
+            data.push(previous[0]);
+            if !(self.ends_with_filler(previous) && previous[0] == pair[0]) {
+                data.push(previous[1]);
+            }
+        }
+    }
+
+    /// Append the rest of the text to `data`.
+    pub fn finish(self, data: &mut String) {
+        if let Some(previous) = self.pending {
+            data.push(previous[0]);
+            if !self.ends_with_filler(previous) {
+                data.push(previous[1]);
+            }
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use std::error::Error;
+use std::fmt;
+
+/// Errors returned by the Playfair board and cypher.
+#[derive(Clone, PartialEq, Debug)]
+pub enum PlayfairError {
+    /// The key phrase contains a character that can not be placed on a board.
+    InvalidKeyCharacter(char),
+    /// A symbol appears more than once on a board.
+    DuplicateSymbol(String),
+    /// A symbol is not on the board.
+    SymbolNotOnBoard(String),
+    /// The board data does not have the expected number of symbols, as `expected`, `found`.
+    InvalidBoardSize(usize, usize),
+    /// The message has no symbols left after filtering.
+    EmptyMessage,
+    /// The ciphertext has an uneven number of symbols, so it can not be broken into pairs.
+    OddLengthCiphertext,
+    /// A ciphertext pair holds the same symbol twice, which Playfair encryption never produces.
+    DoubledCipherPair(char),
+    /// The filler and its fallback are the same symbol.
+    FillerCollision(char),
+    /// A board grid holds a symbol that is not part of the alphabet.
+    SymbolNotInAlphabet(char),
+    /// A board grid lacks these symbols of the alphabet.
+    MissingSymbols(String),
+    /// A row of a board grid does not have as many symbols as the board has columns,
+    /// `row` is counted from 1.
+    InvalidRowLength { row: usize, expected: usize, found: usize },
+}
+
+impl fmt::Display for PlayfairError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlayfairError::InvalidKeyCharacter(c) => write!(f, "invalid character in key phrase: '{}'", c),
+            PlayfairError::DuplicateSymbol(s) => write!(f, "symbol appears more than once: '{}'", s),
+            PlayfairError::SymbolNotOnBoard(s) => write!(f, "symbol is not on the board: '{}'", s),
+            PlayfairError::InvalidBoardSize(expected, found) => {
+                write!(f, "board needs {} symbols, found {}", expected, found)
+            },
+            PlayfairError::EmptyMessage => write!(f, "message is empty"),
+            PlayfairError::OddLengthCiphertext => write!(f, "ciphertext has an odd number of symbols"),
+            PlayfairError::DoubledCipherPair(c) => write!(f, "ciphertext pair repeats '{}'", c),
+            PlayfairError::FillerCollision(c) => write!(f, "filler and fallback are both '{}'", c),
+            PlayfairError::SymbolNotInAlphabet(c) => write!(f, "symbol is not in the alphabet: '{}'", c),
+            PlayfairError::MissingSymbols(s) => write!(f, "board is missing symbols: '{}'", s),
+            PlayfairError::InvalidRowLength { row, expected, found } => {
+                write!(f, "row {} of the grid needs {} symbols, found {}", row, expected, found)
+            },
+        }
+    }
+}
+
+impl Error for PlayfairError {}
@@ -0,0 +1,130 @@
+use std::fmt::{Display, Write};
+use std::hash::Hash;
+
+use super::array::{Board, BoardShape};
+use super::trace::TraceStep;
+
+/// Width and height of a cell, in pixels.
+const CELL: usize = 40;
+
+/// Returns the text with the characters that have a meaning in XML escaped.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Returns the pixel coordinates of the center of a cell.
+fn center(position: [usize; 2]) -> [usize; 2] {
+    [position[1] * CELL + CELL / 2, position[0] * CELL + CELL / 2]
+}
+
+/// Returns the board as a standalone SVG image. If a step is given, the row, column or
+/// rectangle of the pair is shaded and arrows lead from each letter of the pair to the
+/// letter it turns into.
+/// # Arguments
+///
+/// - `board`: board to draw.
+/// - `step`: step of a trace taken on the board, see `PlayfairCypher::trace_encrypt`.
+pub fn board_svg<T>(board: &Board<T>, step: Option<&TraceStep>) -> String where T: Copy + Display + Eq + Hash {
+    let (width, height) = (board.columns() * CELL, board.rows() * CELL);
+    let mut svg = String::new();
+
+    // Writing to a String never fails.
+    let _ = writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" font-family="monospace" font-size="20">"#, width, height, width, height);
+    svg.push_str(r##"<defs><marker id="arrow" viewBox="0 0 10 10" refX="9" refY="5" markerWidth="6" markerHeight="6" orient="auto"><path d="M 0 0 L 10 5 L 0 10 z" fill="#c0392b"/></marker></defs>"##);
+    svg.push('\n');
+
+    if let Some(step) = step {
+        let [p1, p2] = step.input_positions;
+        let (x, y, w, h) = match step.shape {
+            BoardShape::ROW => (0, p1[0], board.columns(), 1),
+            BoardShape::COLUMN => (p1[1], 0, 1, board.rows()),
+            BoardShape::RECTANGLE => (p1[1].min(p2[1]), p1[0].min(p2[0]), p1[1].abs_diff(p2[1]) + 1, p1[0].abs_diff(p2[0]) + 1)
+        };
+        let _ = writeln!(svg, r##"<rect x="{}" y="{}" width="{}" height="{}" fill="#d6eaf8"/>"##, x * CELL, y * CELL, w * CELL, h * CELL);
+        for position in step.input_positions {
+            let _ = writeln!(svg, r##"<rect x="{}" y="{}" width="{}" height="{}" fill="#82e0aa"/>"##, position[1] * CELL, position[0] * CELL, CELL, CELL);
+        }
+        for position in step.output_positions {
+            let _ = writeln!(svg, r##"<rect x="{}" y="{}" width="{}" height="{}" fill="#f5b7b1"/>"##, position[1] * CELL, position[0] * CELL, CELL, CELL);
+        }
+    }
+
+    for row in 0..board.rows() {
+        for column in 0..board.columns() {
+            let [x, y] = center([row, column]);
+            let _ = writeln!(svg, r##"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="#333"/>"##, column * CELL, row * CELL, CELL, CELL);
+            let _ = writeln!(svg, r#"<text x="{}" y="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#, x, y, escape(&board.get_element(row, column).to_string()));
+        }
+    }
+
+    if let Some(step) = step {
+        for (from, to) in step.input_positions.iter().zip(step.output_positions.iter()) {
+            let ([x1, y1], [x2, y2]) = (center(*from), center(*to));
+            let _ = writeln!(svg, r##"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="#c0392b" stroke-width="2" marker-end="url(#arrow)"/>"##, x1, y1, x2, y2);
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Returns a standalone HTML page with the board, followed by one frame per step of the trace.
+/// # Arguments
+///
+/// - `title`: title of the page.
+/// - `board`: board the trace was taken on.
+/// - `steps`: trace, see `PlayfairCypher::trace_encrypt`.
+pub fn trace_html<T>(title: &str, board: &Board<T>, steps: &[TraceStep]) -> String where T: Copy + Display + Eq + Hash {
+    let mut html = String::new();
+
+    let _ = writeln!(html, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>", escape(title));
+    html.push_str("<style>body { font-family: sans-serif; } figure { display: inline-block; margin: 1em; }</style>\n</head>\n<body>\n");
+    let _ = writeln!(html, "<h1>{}</h1>", escape(title));
+    let _ = writeln!(html, "<figure>\n{}<figcaption>Board</figcaption>\n</figure>", board_svg(board, None));
+
+    for (i, step) in steps.iter().enumerate() {
+        let _ = writeln!(html, "<figure>\n{}<figcaption>{}. {}</figcaption>\n</figure>", board_svg(board, Some(step)), i + 1, escape(&step.to_string()));
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::playfair::export::{board_svg, trace_html};
+    use crate::playfair::play::PlayfairCypher;
+
+    #[test]
+    fn svg_board() {
+        let cypher = PlayfairCypher::init("playfair example").unwrap();
+        let svg = board_svg(cypher.board(), None);
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"200\" height=\"200\""));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(25, svg.matches("<text").count());
+        assert_eq!(0, svg.matches("<line").count());
+    }
+
+    #[test]
+    fn svg_step() {
+        let cypher = PlayfairCypher::init("playfair example").unwrap();
+        let step = &cypher.trace_encrypt("hi").unwrap()[0];
+        let svg = board_svg(cypher.board(), Some(step));
+
+        // H (2,4) and I (1,0) span the rectangle of rows 1 to 2, all columns.
+        assert!(svg.contains(r##"<rect x="0" y="40" width="200" height="80" fill="#d6eaf8"/>"##));
+        assert!(svg.contains(r#"<line x1="180" y1="100" x2="20" y2="100""#));
+        assert_eq!(2, svg.matches("<line").count());
+    }
+
+    #[test]
+    fn html_trace() {
+        let cypher = PlayfairCypher::init("playfair example").unwrap();
+        let steps = cypher.trace_encrypt("hide the gold").unwrap();
+        let html = trace_html("Hide <the> gold", cypher.board(), &steps);
+
+        assert!(html.contains("<title>Hide &lt;the&gt; gold</title>"));
+        assert_eq!(steps.len() + 1, html.matches("<figure>").count());
+        assert!(html.contains("<figcaption>1. HI RECTANGLE (2,4) (1,0) swap corners -&gt; BM (2,0) (1,4)</figcaption>"));
+    }
+}
@@ -0,0 +1,136 @@
+/// How the key phrase, followed by the rest of the alphabet, is written into a board.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum FillPattern {
+    /// Row by row, left to right.
+    #[default]
+    Rows,
+    /// Column by column, top to bottom.
+    Columns,
+    /// Clockwise, spiralling inwards from the top left corner.
+    Spiral,
+    /// Along the diagonals running down and to the left, starting in the top left corner.
+    Diagonal,
+    /// Row by row, alternating left to right and right to left (boustrophedon).
+    Snake,
+    /// The key phrase heads the columns of a block, with the rest of the alphabet written
+    /// below it. The columns are read off in alphabetical order of their heading letter,
+    /// and written row by row.
+    KeywordColumnar,
+}
+
+impl FillPattern {
+
+    /// Returns the positions of a square board, as `[row, column]`, in the order they are filled.
+    pub fn positions(&self, size: usize) -> Vec<[usize; 2]> {
+        let mut positions = vec![];
+
+        match self {
+            FillPattern::Rows | FillPattern::KeywordColumnar => {
+                for row in 0..size {
+                    positions.extend((0..size).map(|column| [row, column]));
+                }
+            },
+            FillPattern::Columns => {
+                for column in 0..size {
+                    positions.extend((0..size).map(|row| [row, column]));
+                }
+            },
+            FillPattern::Snake => {
+                for row in 0..size {
+                    positions.extend((0..size).map(|i| [row, if row.is_multiple_of(2) { i } else { size - 1 - i }]));
+                }
+            },
+            FillPattern::Diagonal => {
+                for sum in 0..2 * size - 1 {
+                    positions.extend((0..size).filter(|row| sum >= *row && sum - row < size).map(|row| [row, sum - row]));
+                }
+            },
+            FillPattern::Spiral => {
+                let (mut top, mut bottom, mut left, mut right) = (0, size, 0, size);
+                while top < bottom && left < right {
+                    positions.extend((left..right).map(|column| [top, column]));
+                    positions.extend((top + 1..bottom).map(|row| [row, right - 1]));
+                    if top + 1 < bottom {
+                        positions.extend((left..right - 1).rev().map(|column| [bottom - 1, column]));
+                    }
+                    if left + 1 < right {
+                        positions.extend((top + 1..bottom - 1).rev().map(|row| [row, left]));
+                    }
+                    top += 1;
+                    bottom -= 1;
+                    left += 1;
+                    right -= 1;
+                }
+            }
+        }
+        positions
+    }
+
+    /// Returns the symbols rearranged before they are written into the board. Only
+    /// `KeywordColumnar` changes their order.
+    /// # Arguments
+    ///
+    /// - `symbols`: unique symbols of the key phrase, followed by the rest of the alphabet.
+    /// - `keyword`: number of symbols that come from the key phrase.
+    pub fn arrange(&self, symbols: Vec<char>, keyword: usize) -> Vec<char> {
+        match self {
+            FillPattern::KeywordColumnar if keyword > 0 => {
+                let mut columns: Vec<usize> = (0..keyword).collect();
+                columns.sort_by_key(|x| symbols[*x]);
+
+                columns.into_iter()
+                    .flat_map(|column| symbols.iter().skip(column).step_by(keyword).copied())
+                    .collect()
+            },
+            _ => symbols
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::playfair::alphabet::AlphabetPolicy;
+    use crate::playfair::array::Board;
+    use crate::playfair::fill::FillPattern;
+
+    fn grid(board: &Board<char>) -> String {
+        board.to_string().replace(' ', "").trim_end().replace('\n', " ")
+    }
+
+    fn keyed(phrase: &str, pattern: FillPattern) -> String {
+        grid(&Board::keyed_with_pattern(phrase, AlphabetPolicy::default(), pattern).unwrap())
+    }
+
+    #[test]
+    fn patterns() {
+        assert_eq!("ABCDE FGHIK LMNOP QRSTU VWXYZ", keyed("", FillPattern::Rows));
+        assert_eq!("AFLQV BGMRW CHNSX DIOTY EKPUZ", keyed("", FillPattern::Columns));
+        assert_eq!("ABCDE QRSTF PYZUG OXWVH NMLKI", keyed("", FillPattern::Spiral));
+        assert_eq!("ABDGL CEHMQ FINRU KOSVX PTWYZ", keyed("", FillPattern::Diagonal));
+        assert_eq!("ABCDE KIHGF LMNOP UTSRQ VWXYZ", keyed("", FillPattern::Snake));
+        assert_eq!("AEHLO RUXBF IMPSV YCDGK NQTWZ", keyed("cab", FillPattern::KeywordColumnar));
+        assert_eq!(keyed("", FillPattern::Rows), keyed("", FillPattern::KeywordColumnar));
+    }
+
+    #[test]
+    fn spiral_covers_odd_and_even_boards() {
+        for size in 1..8 {
+            let mut positions = FillPattern::Spiral.positions(size);
+            assert_eq!(size * size, positions.len());
+            positions.sort();
+            positions.dedup();
+            assert_eq!(size * size, positions.len());
+        }
+    }
+
+    #[test]
+    fn keyed_patterns_differ() {
+        let patterns = [FillPattern::Rows, FillPattern::Columns, FillPattern::Spiral, FillPattern::Diagonal, FillPattern::Snake, FillPattern::KeywordColumnar];
+        let mut grids: Vec<String> = patterns.iter().map(|x| keyed("playfair example", *x)).collect();
+        assert_eq!("PLAYF IREXM BCDGH KNOQS TUVWZ", grids[0]);
+        grids.sort();
+        grids.dedup();
+        assert_eq!(patterns.len(), grids.len());
+    }
+}
@@ -0,0 +1,117 @@
+use super::alphabet::AlphabetPolicy;
+
+/// One character of the original text.
+#[derive(Clone, PartialEq, Debug)]
+enum Slot {
+    /// A symbol of the alphabet, `true` if it was lowercase.
+    Symbol(bool),
+    /// Anything the alphabet has no place for, kept as it is.
+    Literal(char),
+}
+
+/// Layout of a text: where its symbols sit, in which case, and the punctuation, whitespace
+/// and other characters around them. Lets the symbols be replaced while the rest of the
+/// text is kept.
+#[derive(Clone, PartialEq, Debug)]
+pub struct TextFormat {
+    slots: Vec<Slot>,
+}
+
+impl TextFormat {
+
+    /// Returns the layout of the text, and its symbols, normalized by the policy. Letters the
+    /// policy drops, like `Q` with `AlphabetPolicy::DropQ`, are left out of both.
+    pub fn record(text: &str, policy: AlphabetPolicy) -> (Self, Vec<char>) {
+        let mut slots = vec![];
+        let mut symbols = vec![];
+
+        for character in text.chars() {
+            let mapped = policy.map(character);
+            if mapped.is_empty() && !character.is_ascii_alphabetic() {
+                slots.push(Slot::Literal(character));
+            }
+            for symbol in mapped {
+                slots.push(Slot::Symbol(character.is_lowercase()));
+                symbols.push(symbol);
+            }
+        }
+        (TextFormat { slots }, symbols)
+    }
+
+    /// Returns the letters written into the layout, each in the case of its slot.
+    /// # Arguments
+    ///
+    /// - `letters`: replacement letters, one per slot, plus the inserted ones.
+    /// - `inserted`: marks the letters without a slot of their own, like fillers. They follow
+    ///   the letter before them, in the same case.
+    /// - `removed`: marks the letters to leave out, like stripped fillers.
+    pub fn write(&self, letters: &[char], inserted: &[bool], removed: &[bool]) -> String {
+        let mut data = String::new();
+        let mut letters = letters.iter().zip(inserted.iter().zip(removed.iter())).peekable();
+        let push = |data: &mut String, letter: char, removed: bool, lowercase: bool| {
+            if !removed {
+                data.push(if lowercase { letter.to_ascii_lowercase() } else { letter });
+            }
+        };
+
+        for slot in &self.slots {
+            match slot {
+                Slot::Literal(character) => data.push(*character),
+                Slot::Symbol(lowercase) => {
+                    if let Some((letter, (_, removed))) = letters.next() {
+                        push(&mut data, *letter, *removed, *lowercase);
+                    }
+                    while let Some((letter, (_, removed))) = letters.next_if(|x| *x.1.0) {
+                        push(&mut data, *letter, *removed, *lowercase);
+                    }
+                }
+            }
+        }
+        for (letter, (_, removed)) in letters {
+            push(&mut data, *letter, *removed, false);
+        }
+        data
+    }
+}
+
+/// Returns a mark for every letter of `full` that is missing from `kept`, a copy of `full`
+/// with some letters taken out. Used to find the fillers inserted or stripped by `Digraphs`.
+pub fn unmatched(full: &[char], kept: &[char]) -> Vec<bool> {
+    let mut kept = kept.iter().peekable();
+    full.iter().map(|x| kept.next_if(|y| *y == x).is_none()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::playfair::alphabet::AlphabetPolicy;
+    use crate::playfair::format::{unmatched, TextFormat};
+
+    #[test]
+    fn record() {
+        let (format, symbols) = TextFormat::record("Quiz, why?", AlphabetPolicy::SplitW);
+        assert_eq!("QUIZVVHY", symbols.iter().collect::<String>());
+        let letters: Vec<char> = "ABCDEFGH".chars().collect();
+        assert_eq!("Abcd, efgh?", format.write(&letters, &[false; 8], &[false; 8]));
+
+        let (_, symbols) = TextFormat::record("Quiz", AlphabetPolicy::DropQ);
+        assert_eq!("UIZ", symbols.iter().collect::<String>());
+    }
+
+    #[test]
+    fn write_inserted_and_removed() {
+        let (format, _) = TextFormat::record("Hello, world!", AlphabetPolicy::default());
+        let letters: Vec<char> = "HELXLOWORLDX".chars().collect();
+        let inserted = unmatched(&letters, &"HELLOWORLD".chars().collect::<Vec<char>>());
+        let none = [false; 12];
+
+        assert_eq!("Helxlo, worldx!", format.write(&letters, &inserted, &none));
+        assert_eq!("Hello, world!", format.write(&letters, &inserted, &inserted));
+    }
+
+    #[test]
+    fn unmatched_letters() {
+        let full: Vec<char> = "LXLX".chars().collect();
+        assert_eq!(vec![false, true, false, false], unmatched(&full, &['L', 'L', 'X']));
+    }
+}
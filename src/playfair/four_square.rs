@@ -0,0 +1,167 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use crate::cipher::Cipher;
+use super::alphabet::AlphabetPolicy;
+use super::array::Board;
+use super::digraph::Digraphs;
+use super::error::PlayfairError;
+
+/// Four-square cipher. Plain alphabet boards sit top left and bottom right, keyed boards
+/// top right and bottom left. The first letter of a pair is looked up in the top left board
+/// and the second letter in the bottom right board, and the pair is replaced by the letters
+/// at the other two corners of the rectangle they span, in the keyed boards.
+/// https://en.wikipedia.org/wiki/Four-square_cipher
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(try_from = "FourSquareConfig"))]
+pub struct FourSquareCipher {
+    #[cfg_attr(feature = "serde", serde(skip_serializing))]
+    plain: Board<char>,
+    first: Board<char>,
+    second: Board<char>,
+    digraphs: Digraphs,
+}
+
+/// Serialized form of a `FourSquareCipher`, checked when loaded. The plain boards
+/// follow from the policy.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct FourSquareConfig {
+    first: Board<char>,
+    second: Board<char>,
+    digraphs: Digraphs,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<FourSquareConfig> for FourSquareCipher {
+    type Error = PlayfairError;
+
+    fn try_from(config: FourSquareConfig) -> Result<Self, Self::Error> {
+        let policy = config.digraphs.policy();
+        config.first.check_square(policy)?;
+        config.second.check_square(policy)?;
+        Ok(FourSquareCipher {
+            plain: Board::keyed("", policy)?,
+            first: config.first,
+            second: config.second,
+            digraphs: config.digraphs,
+        })
+    }
+}
+
+impl FourSquareCipher {
+
+    /// Initialize the two keyed boards with a secret phrase each, merging `J` into `I`.
+    pub fn init(first: &str, second: &str) -> Result<Self, PlayfairError> {
+        Self::init_with_policy(first, second, AlphabetPolicy::default())
+    }
+
+    /// Initialize the two keyed boards with a secret phrase each, reducing the alphabet
+    /// of both phrases and message with the given policy.
+    /// # Arguments
+    ///
+    /// - `first`: secret phrase of the top right board.
+    /// - `second`: secret phrase of the bottom left board.
+    /// - `policy`: how the alphabet is mapped onto the boards.
+    pub fn init_with_policy(first: &str, second: &str, policy: AlphabetPolicy) -> Result<Self, PlayfairError> {
+        Ok(FourSquareCipher {
+            plain: Board::keyed("", policy)?,
+            first: Board::keyed(first, policy)?,
+            second: Board::keyed(second, policy)?,
+            digraphs: Digraphs::init(policy),
+        })
+    }
+
+    /// Set the filler inserted between identical letters of a pair, and used to pad
+    /// an uneven message. See `Digraphs::set_filler`.
+    pub fn set_filler(&mut self, filler: char, fallback: char) -> Result<(), PlayfairError> {
+        self.digraphs.set_filler(filler, fallback)
+    }
+
+    /// Choose whether decryption removes the inserted fillers.
+    pub fn set_strip_filler(&mut self, strip: bool) {
+        self.digraphs.set_strip_filler(strip);
+    }
+
+    /// Returns the encoded pair, taken from the keyed boards.
+    fn encode_pair(&self, pair: [char; 2]) -> Result<[char; 2], PlayfairError> {
+        let p1 = self.plain.get_position(pair[0])?;
+        let p2 = self.plain.get_position(pair[1])?;
+
+        Ok([self.first.get_element(p1[0], p2[1]), self.second.get_element(p2[0], p1[1])])
+    }
+
+    /// Returns the decoded pair, taken from the plain alphabet boards.
+    fn decode_pair(&self, pair: [char; 2]) -> Result<[char; 2], PlayfairError> {
+        let p1 = self.first.get_position(pair[0])?;
+        let p2 = self.second.get_position(pair[1])?;
+
+        Ok([self.plain.get_element(p1[0], p2[1]), self.plain.get_element(p2[0], p1[1])])
+    }
+
+    /// Returns the message encrypted with the four-square cipher.
+    pub fn encrypt(&self, message: &str) -> Result<String, PlayfairError> {
+        let mut data = String::new();
+
+        for pair in self.digraphs.pairs(message)? {
+            data.extend(self.encode_pair(pair)?);
+        }
+        Ok(data)
+    }
+
+    /// Returns the ciphertext decrypted with the four-square cipher. Fillers are removed
+    /// if enabled with `set_strip_filler`.
+    pub fn decrypt(&self, ciphertext: &str) -> Result<String, PlayfairError> {
+        let mut data = vec![];
+
+        for pair in self.digraphs.cipher_pairs(ciphertext)? {
+            data.push(self.decode_pair(pair)?);
+        }
+        Ok(self.digraphs.text(&data))
+    }
+}
+
+impl Cipher for FourSquareCipher {
+
+    fn encrypt(&self, message: &str) -> Result<String, PlayfairError> {
+        FourSquareCipher::encrypt(self, message)
+    }
+
+    fn decrypt(&self, ciphertext: &str) -> Result<String, PlayfairError> {
+        FourSquareCipher::decrypt(self, ciphertext)
+    }
+
+    fn alphabet(&self) -> Vec<char> {
+        self.digraphs.alphabet()
+    }
+
+    fn normalize(&self, text: &str) -> String {
+        self.digraphs.filter(text).into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::playfair::alphabet::AlphabetPolicy;
+    use crate::playfair::error::PlayfairError;
+    use crate::playfair::four_square::FourSquareCipher;
+
+    #[test]
+    fn encrypt_known_example() {
+        let cipher = FourSquareCipher::init_with_policy("example", "keyword", AlphabetPolicy::DropQ).unwrap();
+        assert_eq!("FYGMKYHOBXMFKKKIMD", cipher.encrypt("help me obi wan kenobi").unwrap());
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut cipher = FourSquareCipher::init("example", "keyword").unwrap();
+        cipher.set_strip_filler(true);
+        let encrypted = cipher.encrypt("Attack at dawn").unwrap();
+        assert_eq!("ATTACKATDAWN", cipher.decrypt(&encrypted).unwrap());
+    }
+
+    #[test]
+    fn decrypt_rejects_odd_length() {
+        let cipher = FourSquareCipher::init("example", "keyword").unwrap();
+        assert_eq!(Err(PlayfairError::OddLengthCiphertext), cipher.decrypt("FYG"));
+    }
+}
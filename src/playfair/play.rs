@@ -1,186 +1,261 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use crate::cipher::Cipher;
+use super::alphabet::AlphabetPolicy;
 use super::array::{Board, BoardShape};
+use super::digraph::Digraphs;
+use super::error::PlayfairError;
+use super::fill::FillPattern;
+use super::format::{unmatched, TextFormat};
 
+#[allow(clippy::upper_case_acronyms)]
 enum PlayfairMethod {
     DECODE,
     ENCODE
 }
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(try_from = "PlayfairConfig"))]
 pub struct PlayfairCypher {
     board: Board<char>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing))]
     msg_digested: Vec<[char; 2]>,
+    /// Layout of the digested message, and the fillers inserted into it.
+    #[cfg_attr(feature = "serde", serde(skip_serializing))]
+    msg_format: Option<(TextFormat, Vec<bool>)>,
+    digraphs: Digraphs,
+    preserve_format: bool,
+}
+
+/// Serialized form of a `PlayfairCypher`, checked when loaded.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct PlayfairConfig {
+    board: Board<char>,
+    digraphs: Digraphs,
+    #[serde(default)]
+    preserve_format: bool,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<PlayfairConfig> for PlayfairCypher {
+    type Error = PlayfairError;
+
+    fn try_from(config: PlayfairConfig) -> Result<Self, Self::Error> {
+        let mut cypher = Self::from_board(config.board, config.digraphs.policy())?;
+        cypher.digraphs = config.digraphs;
+        cypher.preserve_format = config.preserve_format;
+        Ok(cypher)
+    }
 }
 
 impl PlayfairCypher {
 
-    /// Initialize a Playfair table with a secret phrase.
-    pub fn init(phrase: &str) -> Result<Self, String> {
-        
-        let mut data: Vec<char> = vec![];
-        let phrase = phrase.replace(" ", "").to_uppercase();
-        let phrase = phrase.as_bytes();
-        const ALPHABET: &[u8; 25] = b"ABCDEFGHIKLMNOPQRSTUVWXYZ";
-    
-        for letter in phrase {
-            if !data.contains(&(*letter as char)) {
-                data.push(*letter as char);
-            }      
-        }
-    
-        for letter in ALPHABET {
-            if !data.contains(&(*letter as char)) {
-                data.push(*letter as char);
-            }
-        }
+    /// Initialize a Playfair table with a secret phrase, merging `J` into `I`.
+    pub fn init(phrase: &str) -> Result<Self, PlayfairError> {
+        Self::init_with_policy(phrase, AlphabetPolicy::default())
+    }
 
-        match data.len() {
-            25 => {
-                Ok(PlayfairCypher { board: Board::init(&data), msg_digested: vec![] })
-            },
-            _ => {
-                Err(String::from("Choose another phrase!"))
-            }
-        }
+    /// Initialize a Playfair table with a secret phrase, reducing the alphabet
+    /// of both phrase and message with the given policy. The table is 5x5, or
+    /// 6x6 for `AlphabetPolicy::Alphanumeric`.
+    /// # Arguments
+    ///
+    /// - `phrase`: secret phrase, spaces are ignored.
+    /// - `policy`: how the alphabet is mapped onto the table.
+    pub fn init_with_policy(phrase: &str, policy: AlphabetPolicy) -> Result<Self, PlayfairError> {
+        Ok(PlayfairCypher {
+            board: Board::keyed(phrase, policy)?,
+            msg_digested: vec![],
+            msg_format: None,
+            digraphs: Digraphs::init(policy),
+            preserve_format: false,
+        })
+    }
+
+    /// Initialize a Playfair table with a secret phrase, written into the table in the
+    /// order of the fill pattern instead of row by row.
+    /// # Arguments
+    ///
+    /// - `phrase`: secret phrase, spaces are ignored.
+    /// - `policy`: how the alphabet is mapped onto the table.
+    /// - `pattern`: order in which the cells of the table are filled.
+    pub fn init_with_pattern(phrase: &str, policy: AlphabetPolicy, pattern: FillPattern) -> Result<Self, PlayfairError> {
+        Self::from_board(Board::keyed_with_pattern(phrase, policy, pattern)?, policy)
+    }
+
+    /// Initialize a Playfair table from an existing board. The board may be rectangular,
+    /// see `Board::check_alphabet` for the symbols it must hold.
+    /// # Arguments
+    ///
+    /// - `board`: board holding the alphabet of the policy.
+    /// - `policy`: how the alphabet of the message is mapped onto the board.
+    pub fn from_board(board: Board<char>, policy: AlphabetPolicy) -> Result<Self, PlayfairError> {
+
+        board.check_alphabet(policy)?;
+        Ok(PlayfairCypher {
+            board,
+            msg_digested: vec![],
+            msg_format: None,
+            digraphs: Digraphs::init(policy),
+            preserve_format: false,
+        })
+    }
+
+    /// Initialize a Playfair table from an explicit grid, as printed in historical
+    /// ciphertexts and puzzle books. See `Board::from_grid`.
+    /// # Arguments
+    ///
+    /// - `grid`: symbols of the board row by row, like `"PLAYF IREXM BCDGH KNOQS TUVWZ"`.
+    /// - `policy`: how the alphabet of the message is mapped onto the board.
+    pub fn from_grid(grid: &str, policy: AlphabetPolicy) -> Result<Self, PlayfairError> {
+        Self::from_board(Board::from_grid(grid, policy)?, policy)
+    }
+
+    /// Returns the board of the cypher.
+    pub fn board(&self) -> &Board<char> {
+        &self.board
     }
 
     /// Returns the processed pairs that are contained within a row of `board`. A pair is encoded
     /// within a row by increasing it's column index by 1. The decoding is the reverse.
     /// # Arguments
     /// 
-    /// - `pair`: Array of two values contained within a `board`.
+    /// - `positions`: Positions of the two values of a pair within a `board`.
     /// 
     /// # Example (encoding)
     /// 
     /// - `[0,2]` and `[1,4]` -> `[0,3]` and `[1,5]` resepectively. 
-    /// Given that `[0,2]` and `[1,4]` are within the same row.
+    ///   Given that `[0,2]` and `[1,4]` are within the same row.
     /// ```
-    fn process_pair_row(&self, pair: [char; 2], method: &PlayfairMethod) -> [char; 2] {
-        let mut data = [pair[0]; 2];
+    fn process_pair_row(&self, positions: [[usize; 2]; 2], method: &PlayfairMethod) -> [char; 2] {
+        let columns = self.board.columns();
 
-        for i in 0..pair.len() {
-            let p = self.board.get_position(pair[i]).unwrap();
+        positions.map(|p| {
             match method {
-                PlayfairMethod::ENCODE => {
-                    let p_updated = if p[1] == self.board.state[0].len()-1 {
-                        self.board.state[p[0]][0]
-                    } else {
-                        self.board.state[p[0]][p[1]+1]
-                    };
-                    data[i] = p_updated;
-                },
-                PlayfairMethod::DECODE => {
-                    let p_updated = if p[1] == 0 {
-                        self.board.state[p[0]][self.board.state[0].len()-1]
-                    } else {
-                        self.board.state[p[0]][p[1]-1]
-                    };
-                    data[i] = p_updated;
-                }
+                PlayfairMethod::ENCODE => self.board.get_element(p[0], (p[1] + 1) % columns),
+                PlayfairMethod::DECODE => self.board.get_element(p[0], (p[1] + columns - 1) % columns)
             }
-        }
-        data
+        })
     }
 
     /// Returns the processed pairs that are contained within a column of `board`. A pair is encoded
     /// within a column by increasing it's row index by 1. The decoding is the reverse.
     /// # Arguments
     /// 
-    /// - `pair`: Array of two values contained within a `board`.
+    /// - `positions`: Positions of the two values of a pair within a `board`.
     /// 
     /// # Example (encoding)
     /// 
     /// - `[0,2]` and `[1,4]` -> `[1,2]` and `[2,4]` resepectively. 
-    /// Given that `[0,2]` and `[1,4]` are within the same column.
+    ///   Given that `[0,2]` and `[1,4]` are within the same column.
     /// ```
-    fn process_pair_column(&self, pair: [char; 2], method: &PlayfairMethod) -> [char; 2] {
-        let mut data = [pair[0]; 2];
+    fn process_pair_column(&self, positions: [[usize; 2]; 2], method: &PlayfairMethod) -> [char; 2] {
+        let rows = self.board.rows();
 
-        for i in 0..pair.len() {
-            let p = self.board.get_position(pair[i]).unwrap();
+        positions.map(|p| {
             match method {
-                PlayfairMethod::ENCODE => {
-                    let p_updated = if p[0] == self.board.state.len()-1 {
-                        self.board.state[0][p[1]]
-                    } else {
-                        self.board.state[p[0]+1][p[1]]
-                    };
-                    data[i] = p_updated;
-                },
-                PlayfairMethod::DECODE => {
-                    let p_updated = if p[0] == 0 {
-                        self.board.state[self.board.state.len()-1][p[1]]
-                    } else {
-                        self.board.state[p[0]-1][p[1]]
-                    };
-                    data[i] = p_updated;
-                }
-            }       
-        }
-        data
+                PlayfairMethod::ENCODE => self.board.get_element((p[0] + 1) % rows, p[1]),
+                PlayfairMethod::DECODE => self.board.get_element((p[0] + rows - 1) % rows, p[1])
+            }
+        })
     }
 
     /// Returns the processed pairs that makes up a rectangle within the context of a `board`. Each pair is encoded
     /// by modifying its column value with the other pair.
     /// # Arguments
     /// 
-    /// - `pair`: Array of two values contained within a `board`.
+    /// - `positions`: Positions of the two values of a pair within a `board`.
     /// 
     /// # Example (encoding)
     /// 
     /// - `[0,2]` and `[1,4]` -> `[0,4]` and `[1,2]` resepectively. 
-    /// Given that `[0,2]` and `[1,4]` make up a rectangle within the `board` context.
+    ///   Given that `[0,2]` and `[1,4]` make up a rectangle within the `board` context.
     /// ```
-    fn process_pair_rectangle(&self, pair: [char; 2]) -> [char; 2] {
-        let mut data = [pair[0]; 2];
-        let p1 = self.board.get_position(pair[0]).unwrap();
-        let p2 = self.board.get_position(pair[1]).unwrap();
+    fn process_pair_rectangle(&self, positions: [[usize; 2]; 2]) -> [char; 2] {
+        let [p1, p2] = positions;
 
-        data[0] = self.board.state[p1[0]][p2[1]];
-        data[1] = self.board.state[p2[0]][p1[1]];
-        data
+        [self.board.get_element(p1[0], p2[1]), self.board.get_element(p2[0], p1[1])]
     }
 
     /// Returns the processed pairs depending on whether they formed a `column`, `row` or a `rectangle`.
+    /// The position of each value is looked up once, and used for both the shape and the result.
     /// 
     /// # Arguments
     /// 
     /// - `pair`: Array of two values contained within a `board`.
-    /// - `method`: How to process the pair, can be either `encode` or `decode`.
+    /// - `method`: How to process the pair, can be either `encode` or `decode`. Decoding fails
+    ///   on a pair of identical symbols, which encoding never produces.
     /// ```
-    fn process_pair(&self, pair: [char; 2], method: &PlayfairMethod) -> [char; 2] {
+    fn process_pair(&self, pair: [char; 2], method: &PlayfairMethod) -> Result<[char; 2], PlayfairError> {
 
-        let shape = self.board.get_shape(pair);
+        if matches!(method, PlayfairMethod::DECODE) && pair[0] == pair[1] {
+            return Err(PlayfairError::DoubledCipherPair(pair[0]));
+        }
+        let positions = [self.board.get_position(pair[0])?, self.board.get_position(pair[1])?];
 
-        match shape {
+        match BoardShape::from_positions(positions[0], positions[1]) {
             BoardShape::COLUMN => {
-                self.process_pair_column(pair, method)
+                Ok(self.process_pair_column(positions, method))
             },
             BoardShape::ROW => {
-                self.process_pair_row(pair, method)
+                Ok(self.process_pair_row(positions, method))
             },
             BoardShape::RECTANGLE => {
-                self.process_pair_rectangle(pair)
+                Ok(self.process_pair_rectangle(positions))
             }
         }
     }
 
-    /// Converts a string slice into a byte slice, and breaks it down into
-    /// pairs of two. If uneven, a byte encoded `X` is added to the last pair.
+    /// Returns the encoded pair.
+    pub(crate) fn encode_pair(&self, pair: [char; 2]) -> Result<[char; 2], PlayfairError> {
+        self.process_pair(pair, &PlayfairMethod::ENCODE)
+    }
+
+    /// Returns the decoded pair.
+    pub(crate) fn decode_pair(&self, pair: [char; 2]) -> Result<[char; 2], PlayfairError> {
+        self.process_pair(pair, &PlayfairMethod::DECODE)
+    }
+
+    /// Returns the digraph settings of the cypher.
+    pub(crate) fn digraphs(&self) -> &Digraphs {
+        &self.digraphs
+    }
+
+    /// Set the filler inserted between identical letters of a pair, and used to pad
+    /// an uneven message. The `fallback` is used when the repeated letter is the filler itself.
     /// # Arguments
-    /// 
-    /// - `message`: byte encoded text message.
-    pub fn digest(&mut self, message: &str) {
+    ///
+    /// - `filler`: letter inserted between identical letters, `X` by default.
+    /// - `fallback`: letter used instead of `filler` when the repeated letter is `filler`, `Q` by default.
+    pub fn set_filler(&mut self, filler: char, fallback: char) -> Result<(), PlayfairError> {
+        self.digraphs.set_filler(filler, fallback)
+    }
 
-        let mut data = vec![];
-        let mut message = self.filter(message);
+    /// Choose whether decoding removes the fillers inserted by `digest`.
+    pub fn set_strip_filler(&mut self, strip: bool) {
+        self.digraphs.set_strip_filler(strip);
+    }
 
-        if message.len() % 2 != 0 {
-            message.push('X' as u8);
-        }
+    /// Choose whether encoding and decoding keep the case, whitespace, punctuation and other
+    /// characters outside the alphabet of the text. Only the letters are replaced, fillers are
+    /// written next to the letter they follow. Off by default, and not used by the streams.
+    pub fn set_preserve_format(&mut self, preserve: bool) {
+        self.preserve_format = preserve;
+    }
 
-        for i in (0..message.len()).step_by(2) {
-            data.push([message[i] as char, message[i+1] as char]);
-        }
-        self.msg_digested = data;
+    /// Filters a string slice down to the alphabet of the cipher, and breaks it down into
+    /// pairs of two. A filler is inserted between identical letters of a pair,
+    /// and added to the last pair if uneven.
+    /// # Arguments
+    /// 
+    /// - `message`: text message.
+    pub fn digest(&mut self, message: &str) -> Result<(), PlayfairError> {
+        self.msg_digested = self.digraphs.pairs(message)?;
+        self.msg_format = self.preserve_format.then(|| {
+            let (format, symbols) = TextFormat::record(message, self.digraphs.policy());
+            (format, unmatched(&self.msg_digested.concat(), &symbols))
+        });
+        Ok(())
     }
 
     /// Show debug information for digested message.
@@ -189,42 +264,277 @@ impl PlayfairCypher {
         for pair in &self.msg_digested {
             print!("{} {} ", pair[0], pair[1]);
         }
-        print!("\n");
+        println!();
     }
 
-    /// Perform PlayfairCypher encoding/decoding on the digested message.
+    /// Perform PlayfairCypher encoding/decoding on the given pairs.
     /// # Arguments
     /// 
+    /// - `pairs`: pairs of letters on the board.
     /// - `method`: specify the method, either `encode` or `decode`.
-    fn playfair(&mut self, method: PlayfairMethod) -> String {
+    fn playfair(&self, pairs: &[[char; 2]], method: PlayfairMethod) -> Result<Vec<[char; 2]>, PlayfairError> {
 
-        let mut data = String::new();
+        let mut data = vec![];
 
-        for pair in &self.msg_digested {
-            let pair_encoded = self.process_pair([pair[0], pair[1]], &method);
-            
-            data.push(pair_encoded[0] as char);
-            data.push(pair_encoded[1] as char);
+        for pair in pairs {
+            data.push(self.process_pair([pair[0], pair[1]], &method)?);
+        }
+        Ok(data)
+    }
+
+    /// Returns the processed pairs as text. With a format, the letters are written into it,
+    /// else they are joined. Fillers are removed if enabled with `set_strip_filler`.
+    /// # Arguments
+    ///
+    /// - `data`: processed pairs.
+    /// - `format`: layout of the text, and the fillers inserted into it.
+    /// - `method`: how the pairs were processed, either `encode` or `decode`.
+    fn text(&self, data: &[[char; 2]], format: Option<&(TextFormat, Vec<bool>)>, method: PlayfairMethod) -> String {
+        let letters = data.concat();
+        let text: String = match method {
+            PlayfairMethod::ENCODE => letters.iter().collect(),
+            PlayfairMethod::DECODE => self.digraphs.text(data)
+        };
+
+        match format {
+            Some((format, inserted)) => {
+                format.write(&letters, inserted, &unmatched(&letters, &text.chars().collect::<Vec<char>>()))
+            },
+            None => text
+        }
+    }
+
+    /// Encode digested message using Playfair encoding.
+    pub fn playfair_encode(&mut self) -> Result<String, PlayfairError> {
+        let data = self.playfair(&self.msg_digested, PlayfairMethod::ENCODE)?;
+        Ok(self.text(&data, self.msg_format.as_ref(), PlayfairMethod::ENCODE))
+    }
+
+    /// Decode digested Playfair message. Fillers are removed if enabled with `set_strip_filler`.
+    pub fn playfair_decode(&mut self) -> Result<String, PlayfairError> {
+        let data = self.playfair(&self.msg_digested, PlayfairMethod::DECODE)?;
+        Ok(self.text(&data, self.msg_format.as_ref(), PlayfairMethod::DECODE))
+    }
+
+    /// Returns the message encrypted with Playfair encoding. Unlike `digest` and
+    /// `playfair_encode`, the cypher is left untouched, so it can be shared between threads.
+    /// # Arguments
+    ///
+    /// - `message`: text message.
+    pub fn encrypt(&self, message: &str) -> Result<String, PlayfairError> {
+        let pairs = self.digraphs.pairs(message)?;
+        let format = self.preserve_format.then(|| {
+            let (format, symbols) = TextFormat::record(message, self.digraphs.policy());
+            (format, unmatched(&pairs.concat(), &symbols))
+        });
+        Ok(self.text(&self.playfair(&pairs, PlayfairMethod::ENCODE)?, format.as_ref(), PlayfairMethod::ENCODE))
+    }
+
+    /// Returns the ciphertext decrypted with Playfair decoding. Fillers are removed
+    /// if enabled with `set_strip_filler`.
+    /// # Arguments
+    ///
+    /// - `ciphertext`: encrypted message, must have an even number of letters.
+    pub fn decrypt(&self, ciphertext: &str) -> Result<String, PlayfairError> {
+        let pairs = self.digraphs.cipher_pairs(ciphertext)?;
+        let format = self.preserve_format.then(|| {
+            (TextFormat::record(ciphertext, self.digraphs.policy()).0, vec![false; pairs.len() * 2])
+        });
+        Ok(self.text(&self.playfair(&pairs, PlayfairMethod::DECODE)?, format.as_ref(), PlayfairMethod::DECODE))
+    }
+}
+
+impl Cipher for PlayfairCypher {
+
+    fn encrypt(&self, message: &str) -> Result<String, PlayfairError> {
+        PlayfairCypher::encrypt(self, message)
+    }
+
+    fn decrypt(&self, ciphertext: &str) -> Result<String, PlayfairError> {
+        PlayfairCypher::decrypt(self, ciphertext)
+    }
+
+    fn alphabet(&self) -> Vec<char> {
+        self.digraphs.alphabet()
+    }
+
+    fn normalize(&self, text: &str) -> String {
+        self.digraphs.filter(text).into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::sync::Arc;
+    use std::thread;
+    use crate::playfair::alphabet::AlphabetPolicy;
+    use crate::playfair::array::Board;
+    use crate::playfair::error::PlayfairError;
+    use crate::playfair::play::PlayfairCypher;
+
+    #[test]
+    fn digest_splits_doubled_letters() {
+        let mut cypher = PlayfairCypher::init("Playfair example").unwrap();
+        cypher.digest("balloon").unwrap();
+        assert_eq!(vec![['B', 'A'], ['L', 'X'], ['L', 'O'], ['O', 'N']], cypher.msg_digested);
+    }
+
+    #[test]
+    fn digest_uses_fallback_for_doubled_filler() {
+        let mut cypher = PlayfairCypher::init("Playfair example").unwrap();
+        cypher.digest("xxx").unwrap();
+        assert_eq!(vec![['X', 'Q'], ['X', 'Q'], ['X', 'Q']], cypher.msg_digested);
+    }
+
+    #[test]
+    fn decode_strips_filler() {
+        let mut cypher = PlayfairCypher::init("Playfair example").unwrap();
+        cypher.set_strip_filler(true);
+        cypher.digest("hello balloon").unwrap();
+        let encoded = cypher.playfair_encode().unwrap();
+        cypher.digest(&encoded).unwrap();
+        assert_eq!("HELLOBALLOON", cypher.playfair_decode().unwrap());
+    }
+
+    #[test]
+    fn init_accepts_j_in_phrase() {
+        let mut cypher = PlayfairCypher::init("Jump Jack").unwrap();
+        cypher.digest("jump jack").unwrap();
+        assert_eq!(vec![['I', 'U'], ['M', 'P'], ['I', 'A'], ['C', 'K']], cypher.msg_digested);
+    }
+
+    #[test]
+    fn init_with_policy_round_trip() {
+        let mut cypher = PlayfairCypher::init_with_policy("Quick fox", AlphabetPolicy::DropQ).unwrap();
+        cypher.digest("quiet quay").unwrap();
+        let encoded = cypher.playfair_encode().unwrap();
+        cypher.digest(&encoded).unwrap();
+        assert_eq!("UIETUAYX", cypher.playfair_decode().unwrap());
+    }
+
+    #[test]
+    fn alphanumeric_round_trip() {
+        let mut cypher = PlayfairCypher::init_with_policy("Grid 42", AlphabetPolicy::Alphanumeric).unwrap();
+        cypher.set_strip_filler(true);
+        let encrypted = cypher.encrypt("Meet at 0900 on 2024-11-05").unwrap();
+        assert!(encrypted.chars().any(|x| x.is_ascii_digit()));
+        assert_eq!("MEETAT0900ON20241105", cypher.decrypt(&encrypted).unwrap());
+    }
+
+    #[test]
+    fn set_filler_rejects_same_fallback() {
+        let mut cypher = PlayfairCypher::init("Playfair example").unwrap();
+        assert_eq!(Err(PlayfairError::FillerCollision('Z')), cypher.set_filler('Z', 'Z'));
+        assert_eq!(Err(PlayfairError::SymbolNotOnBoard(String::from("J"))), cypher.set_filler('J', 'Q'));
+        assert!(cypher.set_filler('Z', 'Y').is_ok());
+    }
+
+    #[test]
+    fn encrypt_decrypt_round_trip() {
+        let mut cypher = PlayfairCypher::init("Playfair example").unwrap();
+        cypher.set_strip_filler(true);
+        let encrypted = cypher.encrypt("Hide the gold in the tree stump").unwrap();
+        assert_eq!("BMODZBXDNABEKUDMUIXMMOUVIF", encrypted);
+        assert_eq!("HIDETHEGOLDINTHETREESTUMP", cypher.decrypt(&encrypted).unwrap());
+    }
+
+    #[test]
+    fn decrypt_rejects_odd_length() {
+        let cypher = PlayfairCypher::init("Playfair example").unwrap();
+        assert_eq!(Err(PlayfairError::OddLengthCiphertext), cypher.decrypt("BMO"));
+    }
+
+    #[test]
+    fn encrypt_shared_between_threads() {
+        let cypher = Arc::new(PlayfairCypher::init("Playfair example").unwrap());
+        let handles: Vec<_> = (0..4).map(|_| {
+            let cypher = Arc::clone(&cypher);
+            thread::spawn(move || cypher.encrypt("Hide the gold").unwrap())
+        }).collect();
+
+        for handle in handles {
+            assert_eq!("BMODZBXDNAGE", handle.join().unwrap());
         }
-        data
     }
 
-    /// Encode message using Playfair encoding.
-    pub fn playfair_encode(&mut self) -> String {
-        self.playfair(PlayfairMethod::ENCODE)
+    #[test]
+    fn init_rejects_invalid_key_character() {
+        assert_eq!(Err(PlayfairError::InvalidKeyCharacter('!')), PlayfairCypher::init("Playfair!").map(|_| ()));
     }
 
-    /// Decode encoded Playfair message.
-    pub fn playfair_decode(&mut self) -> String {
-        self.playfair(PlayfairMethod::DECODE)
+    #[test]
+    fn preserve_format_round_trip() {
+        let mut cypher = PlayfairCypher::init("Playfair example").unwrap();
+        cypher.set_preserve_format(true);
+        cypher.set_strip_filler(true);
+
+        let encrypted = cypher.encrypt("Hello, World! It's 9 o'clock.").unwrap();
+        assert_eq!("Dmyran, Vqcrb! Ez'k 9 n'danbn.", encrypted);
+        assert_eq!("Hello, World! It's 9 o'clock.", cypher.decrypt(&encrypted).unwrap());
+
+        cypher.set_strip_filler(false);
+        assert_eq!("Helxlo, World! It's 9 o'clock.", cypher.decrypt(&encrypted).unwrap());
     }
 
-    /// Return a filtered array. Removing everything except the byte representation of the uppercase english
-    /// alphabet, except for the letter `J`.
-    /// https://en.wikipedia.org/wiki/Playfair_cipher
-    fn filter(&self, msg: &str) -> Vec<u8> {
-        let msg = msg.to_uppercase().as_bytes().to_vec();
-        let msg: Vec<u8> = msg.into_iter().filter(|&x| x >= 65 && x <= 90 && x != 74).collect();
-        msg
+    #[test]
+    fn preserve_format_digest() {
+        let mut cypher = PlayfairCypher::init("Playfair example").unwrap();
+        cypher.set_preserve_format(true);
+        cypher.digest("Hide the gold!").unwrap();
+        let encoded = cypher.playfair_encode().unwrap();
+        assert_eq!("Bmod zbx dnage!", encoded);
+
+        cypher.set_strip_filler(true);
+        cypher.digest(&encoded).unwrap();
+        assert_eq!("Hide the gold!", cypher.playfair_decode().unwrap());
+    }
+
+    #[test]
+    fn from_grid() {
+        let keyed = PlayfairCypher::init("Playfair example").unwrap();
+        let grid = PlayfairCypher::from_grid("PLAYF IREXM bcdgh\nKNOQS TUVWZ", AlphabetPolicy::default()).unwrap();
+        assert_eq!(keyed.encrypt("Hide the gold").unwrap(), grid.encrypt("Hide the gold").unwrap());
+        assert!(PlayfairCypher::from_grid("PLAYFIREXMBCDGHKNOQSTUVWZ", AlphabetPolicy::default()).is_ok());
+    }
+
+    #[test]
+    fn rectangular_board_round_trip() {
+        let symbols: Vec<char> = "ABCDEFGHIJKLMNOPQRSTUVWXYZ012345".chars().collect();
+        let policy = AlphabetPolicy::Alphanumeric;
+        let cypher = PlayfairCypher::from_board(Board::init(&symbols, 4, 8).unwrap(), policy).unwrap();
+
+        assert_eq!("ABAIBI", cypher.encrypt("HAYAAJ").unwrap());
+        let ciphertext = cypher.encrypt("Meet me at gate 5 at 4").unwrap();
+        assert_eq!("MEETMEATGATE5AT4", cypher.decrypt(&ciphertext).unwrap());
+
+        let letters = Board::init(&symbols[..25], 5, 5).unwrap();
+        assert_eq!(Some(PlayfairError::MissingSymbols(String::from("Z"))), PlayfairCypher::from_board(letters, policy).err());
+        let digits = Board::init(&symbols, 4, 8).unwrap();
+        assert_eq!(Some(PlayfairError::SymbolNotInAlphabet('J')), PlayfairCypher::from_board(digits, AlphabetPolicy::default()).err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn decrypt_rejects_doubled_pairs() {
+        let cypher = PlayfairCypher::init("Playfair example").unwrap();
+        assert_eq!(Err(PlayfairError::DoubledCipherPair('A')), cypher.decrypt("AABB"));
+        assert_eq!(Err(PlayfairError::DoubledCipherPair('B')), cypher.decrypt("ACBB"));
+        assert!(cypher.decrypt("BMOD").is_ok());
+    }
+
+    #[test]
+    fn from_grid_errors() {
+        let policy = AlphabetPolicy::default();
+        assert!(matches!(PlayfairCypher::from_grid("PLAYF IREXM BCDGH KNOQS TUVW", policy), Err(PlayfairError::InvalidRowLength { row: 5, expected: 5, found: 4 })));
+        assert!(matches!(PlayfairCypher::from_grid("PLAYF IREXMB CDGH KNOQS TUVWZ", policy), Err(PlayfairError::InvalidRowLength { row: 2, expected: 5, found: 6 })));
+        assert!(matches!(PlayfairCypher::from_grid("PLAYF IREXM BCDGH KNOQS TUVWJ", policy), Err(PlayfairError::SymbolNotInAlphabet('J'))));
+        assert_eq!(Some(PlayfairError::DuplicateSymbol(String::from("P"))), PlayfairCypher::from_grid("PLAYF IREXM BCDGH KNOQS TUVWP", policy).err());
+        assert_eq!(Some(PlayfairError::MissingSymbols(String::from("Z"))), PlayfairCypher::from_grid("PLAYFIREXMBCDGHKNOQSTUVW", policy).err());
+    }
+
+    #[test]
+    fn digest_rejects_empty_message() {
+        let mut cypher = PlayfairCypher::init("Playfair example").unwrap();
+        assert_eq!(Err(PlayfairError::EmptyMessage), cypher.digest("1234 ?!"));
+    }
+}
@@ -0,0 +1,116 @@
+use std::fmt::Display;
+use std::hash::Hash;
+use std::io::{self, IsTerminal};
+
+use super::array::{Board, BoardShape};
+use super::trace::TraceStep;
+
+const RESET: &str = "\x1b[0m";
+/// Bold green on blue, for the two letters of the pair.
+const INPUT: &str = "\x1b[1;32;44m";
+/// Bold red on blue, for the two letters the pair turns into.
+const OUTPUT: &str = "\x1b[1;31;44m";
+/// Blue background, for the rest of the row, column or rectangle.
+const SHAPE: &str = "\x1b[44m";
+
+/// Draws a board with the cells of one step of a trace highlighted.
+pub struct BoardRenderer {
+    color: bool,
+}
+
+impl BoardRenderer {
+
+    /// Initialize a renderer, with or without ANSI colors.
+    pub fn init(color: bool) -> Self {
+        BoardRenderer { color }
+    }
+
+    /// Initialize a renderer that only uses colors if stdout is a terminal.
+    pub fn for_stdout() -> Self {
+        Self::init(io::stdout().is_terminal())
+    }
+
+    /// Returns `True` if the position is part of the row, column or rectangle of the step.
+    fn in_shape(step: &TraceStep, position: [usize; 2]) -> bool {
+        let [p1, p2] = step.input_positions;
+        let between = |i: usize| p1[i].min(p2[i]) <= position[i] && position[i] <= p1[i].max(p2[i]);
+
+        match step.shape {
+            BoardShape::ROW => position[0] == p1[0],
+            BoardShape::COLUMN => position[1] == p1[1],
+            BoardShape::RECTANGLE => between(0) && between(1)
+        }
+    }
+
+    /// Returns the board drawn with the cells of the step highlighted, followed by the step.
+    /// Without colors the letters of the pair are marked `[X]`, the letters they turn into `(X)`,
+    /// and the rest of the row, column or rectangle `·X·`.
+    /// # Arguments
+    ///
+    /// - `board`: board the step was taken on.
+    /// - `step`: step of a trace, see `PlayfairCypher::trace_encrypt`.
+    pub fn render<T>(&self, board: &Board<T>, step: &TraceStep) -> String where T: Copy + Display + Eq + Hash {
+        let mut data = String::new();
+
+        for row in 0..board.rows() {
+            for column in 0..board.columns() {
+                let position = [row, column];
+                let element = board.get_element(row, column);
+                let input = step.input_positions.contains(&position);
+                let output = step.output_positions.contains(&position);
+
+                let cell = match (self.color, input, output) {
+                    (true, true, _) => format!("{} {} {}", INPUT, element, RESET),
+                    (true, false, true) => format!("{} {} {}", OUTPUT, element, RESET),
+                    (true, false, false) if Self::in_shape(step, position) => format!("{} {} {}", SHAPE, element, RESET),
+                    (false, true, _) => format!("[{}]", element),
+                    (false, false, true) => format!("({})", element),
+                    (false, false, false) if Self::in_shape(step, position) => format!("·{}·", element),
+                    _ => format!(" {} ", element)
+                };
+                data.push_str(&cell);
+            }
+            data.push('\n');
+        }
+        data.push_str(&step.to_string());
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::playfair::play::PlayfairCypher;
+    use crate::playfair::render::BoardRenderer;
+
+    #[test]
+    fn render_plain() {
+        let cypher = PlayfairCypher::init("playfair example").unwrap();
+        let step = &cypher.trace_encrypt("hi").unwrap()[0];
+        let expected = " P  L  A  Y  F \n\
+                        [I]·R··E··X·(M)\n\
+                        (B)·C··D··G·[H]\n \
+                         K  N  O  Q  S \n \
+                         T  U  V  W  Z \n\
+                        HI RECTANGLE (2,4) (1,0) swap corners -> BM (2,0) (1,4)";
+        assert_eq!(expected, BoardRenderer::init(false).render(cypher.board(), step));
+    }
+
+    #[test]
+    fn render_plain_row() {
+        let cypher = PlayfairCypher::init("playfair example").unwrap();
+        let step = &cypher.trace_encrypt("pl").unwrap()[0];
+        let rendered = BoardRenderer::init(false).render(cypher.board(), step);
+        assert!(rendered.starts_with("[P][L](A)·Y··F·\n I  R  E  X  M \n"));
+    }
+
+    #[test]
+    fn render_color() {
+        let cypher = PlayfairCypher::init("playfair example").unwrap();
+        let step = &cypher.trace_encrypt("pl").unwrap()[0];
+        let rendered = BoardRenderer::init(true).render(cypher.board(), step);
+
+        assert!(rendered.starts_with("\x1b[1;32;44m P \x1b[0m\x1b[1;32;44m L \x1b[0m\x1b[1;31;44m A \x1b[0m\x1b[44m Y \x1b[0m"));
+        assert!(rendered.contains("\n I "));
+    }
+}
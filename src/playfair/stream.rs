@@ -0,0 +1,200 @@
+use std::io::{self, Read, Write};
+use std::str;
+
+use super::error::PlayfairError;
+use super::play::PlayfairCypher;
+
+/// Number of bytes read from the input at a time.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Returns the cypher error as an io error, so it can travel through `Read` and `Write` code.
+fn invalid_data(err: PlayfairError) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+/// Read `reader` chunk by chunk, and call `process` with the text of each chunk.
+/// UTF-8 sequences split between two chunks are carried over to the next one.
+fn read_chunks<R, F>(mut reader: R, mut process: F) -> io::Result<()>
+where R: Read, F: FnMut(&str) -> io::Result<()> {
+
+    let mut buffer = vec![0; CHUNK_SIZE];
+    let mut carry: Vec<u8> = vec![];
+
+    loop {
+        let n = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err)
+        };
+        carry.extend_from_slice(&buffer[..n]);
+
+        let valid = match str::from_utf8(&carry) {
+            Ok(text) => text.len(),
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err))
+        };
+        process(str::from_utf8(&carry[..valid]).expect("valid UTF-8"))?;
+        carry.drain(..valid);
+    }
+
+    if !carry.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "stream ends inside a UTF-8 sequence"));
+    }
+    Ok(())
+}
+
+impl PlayfairCypher {
+
+    /// Encrypt everything read from `reader` and write the ciphertext to `writer`, one chunk
+    /// at a time, so memory stays constant regardless of the input size. Produces the
+    /// same ciphertext as `encrypt` on the whole input, unless `set_preserve_format` is on:
+    /// the stream always writes bare letters.
+    /// # Arguments
+    ///
+    /// - `reader`: UTF-8 encoded text.
+    /// - `writer`: receives the ciphertext.
+    pub fn encrypt_stream<R: Read, W: Write>(&self, reader: R, mut writer: W) -> io::Result<()> {
+
+        let digraphs = self.digraphs();
+        let mut pairs = digraphs.pair_builder();
+        let mut empty = true;
+
+        read_chunks(reader, |text| {
+            let mut data = String::new();
+            for symbol in digraphs.filter(text) {
+                empty = false;
+                if let Some(pair) = pairs.push(symbol) {
+                    data.extend(self.encode_pair(pair).map_err(invalid_data)?);
+                }
+            }
+            writer.write_all(data.as_bytes())
+        })?;
+
+        if empty {
+            return Err(invalid_data(PlayfairError::EmptyMessage));
+        }
+        if let Some(pair) = pairs.finish() {
+            let data: String = self.encode_pair(pair).map_err(invalid_data)?.iter().collect();
+            writer.write_all(data.as_bytes())?;
+        }
+        writer.flush()
+    }
+
+    /// Decrypt everything read from `reader` and write the plaintext to `writer`, one chunk
+    /// at a time. Produces the same plaintext as `decrypt` on the whole input, unless
+    /// `set_preserve_format` is on: the stream always writes bare letters.
+    /// # Arguments
+    ///
+    /// - `reader`: UTF-8 encoded ciphertext, must have an even number of letters.
+    /// - `writer`: receives the plaintext.
+    pub fn decrypt_stream<R: Read, W: Write>(&self, reader: R, mut writer: W) -> io::Result<()> {
+
+        let digraphs = self.digraphs();
+        let mut text = digraphs.text_writer();
+        let mut pending: Option<char> = None;
+        let mut empty = true;
+
+        read_chunks(reader, |chunk| {
+            let mut data = String::new();
+            for symbol in digraphs.filter(chunk) {
+                empty = false;
+                match pending.take() {
+                    None => pending = Some(symbol),
+                    Some(first) => text.push(self.decode_pair([first, symbol]).map_err(invalid_data)?, &mut data)
+                }
+            }
+            writer.write_all(data.as_bytes())
+        })?;
+
+        if empty {
+            return Err(invalid_data(PlayfairError::EmptyMessage));
+        }
+        if pending.is_some() {
+            return Err(invalid_data(PlayfairError::OddLengthCiphertext));
+        }
+        let mut data = String::new();
+        text.finish(&mut data);
+        writer.write_all(data.as_bytes())?;
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::io::{self, Read};
+    use crate::playfair::play::PlayfairCypher;
+
+    /// Reader returning at most `size` bytes per read, to exercise chunk boundaries.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        size: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.size.min(buf.len()).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn encrypt_stream_matches_encrypt() {
+        let cypher = PlayfairCypher::init("Playfair example").unwrap();
+        let message = "Hello, balloon — ünïcode öö! Hide the gold in the tree stump.";
+
+        for size in [1, 2, 3, 7] {
+            let mut output = vec![];
+            cypher.encrypt_stream(Trickle { data: message.as_bytes(), size }, &mut output).unwrap();
+            assert_eq!(cypher.encrypt(message).unwrap(), String::from_utf8(output).unwrap());
+        }
+    }
+
+    #[test]
+    fn decrypt_stream_matches_decrypt() {
+        let mut cypher = PlayfairCypher::init("Playfair example").unwrap();
+        cypher.set_strip_filler(true);
+        let encrypted = cypher.encrypt("Hello balloon, hide the gold in the tree stump").unwrap();
+
+        for size in [1, 3] {
+            let mut output = vec![];
+            cypher.decrypt_stream(Trickle { data: encrypted.as_bytes(), size }, &mut output).unwrap();
+            assert_eq!(cypher.decrypt(&encrypted).unwrap(), String::from_utf8(output).unwrap());
+        }
+    }
+
+    #[test]
+    fn encrypt_stream_rejects_empty_message() {
+        let cypher = PlayfairCypher::init("Playfair example").unwrap();
+        let err = cypher.encrypt_stream("1234, 5678!".as_bytes(), io::sink()).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+    }
+
+    #[test]
+    fn decrypt_stream_rejects_odd_length() {
+        let cypher = PlayfairCypher::init("Playfair example").unwrap();
+        let err = cypher.decrypt_stream("BMO".as_bytes(), io::sink()).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+    }
+
+    #[test]
+    fn streams_ignore_preserve_format() {
+        let mut cypher = PlayfairCypher::init("Playfair example").unwrap();
+        cypher.set_preserve_format(true);
+        let message = "Hide the gold, in the tree stump!";
+        let encrypted = cypher.encrypt(message).unwrap();
+
+        let mut output = vec![];
+        cypher.encrypt_stream(message.as_bytes(), &mut output).unwrap();
+        assert_eq!("BMODZBXDNABEKUDMUIXMMOUVIF", String::from_utf8(output).unwrap());
+        assert_eq!("Bmod zbx dnab, ek udm uixmm ouvif!", encrypted);
+
+        let mut output = vec![];
+        cypher.decrypt_stream(encrypted.as_bytes(), &mut output).unwrap();
+        assert_eq!("HIDETHEGOLDINTHETREXESTUMP", String::from_utf8(output).unwrap());
+        assert_eq!("Hide the gold, in the trexe stump!", cypher.decrypt(&encrypted).unwrap());
+    }
+}
@@ -0,0 +1,145 @@
+use std::fmt::{self, Display};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::array::BoardShape;
+use super::error::PlayfairError;
+use super::play::PlayfairCypher;
+
+/// How the letters of a pair move on the board.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TraceRule {
+    /// Each letter is replaced by the letter to its right, wrapping around.
+    RIGHT,
+    /// Each letter is replaced by the letter to its left, wrapping around.
+    LEFT,
+    /// Each letter is replaced by the letter below it, wrapping around.
+    DOWN,
+    /// Each letter is replaced by the letter above it, wrapping around.
+    UP,
+    /// Each letter is replaced by the letter in its row and the column of the other letter.
+    CORNERS
+}
+
+impl TraceRule {
+
+    /// Returns the rule applied to the shape, when encoding or decoding.
+    fn init(shape: BoardShape, encode: bool) -> Self {
+        match (shape, encode) {
+            (BoardShape::ROW, true) => TraceRule::RIGHT,
+            (BoardShape::ROW, false) => TraceRule::LEFT,
+            (BoardShape::COLUMN, true) => TraceRule::DOWN,
+            (BoardShape::COLUMN, false) => TraceRule::UP,
+            (BoardShape::RECTANGLE, _) => TraceRule::CORNERS
+        }
+    }
+}
+
+impl Display for TraceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rule = match self {
+            TraceRule::RIGHT => "shift right",
+            TraceRule::LEFT => "shift left",
+            TraceRule::DOWN => "shift down",
+            TraceRule::UP => "shift up",
+            TraceRule::CORNERS => "swap corners"
+        };
+        write!(f, "{}", rule)
+    }
+}
+
+/// What happened to one pair, as `[row, column]` positions on the board.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TraceStep {
+    pub input: [char; 2],
+    pub input_positions: [[usize; 2]; 2],
+    pub shape: BoardShape,
+    pub rule: TraceRule,
+    pub output: [char; 2],
+    pub output_positions: [[usize; 2]; 2],
+}
+
+impl Display for TraceStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [p1, p2] = self.input_positions;
+        let [o1, o2] = self.output_positions;
+
+        write!(f, "{}{} {:?} ({},{}) ({},{}) {} -> {}{} ({},{}) ({},{})",
+            self.input[0], self.input[1], self.shape, p1[0], p1[1], p2[0], p2[1], self.rule,
+            self.output[0], self.output[1], o1[0], o1[1], o2[0], o2[1])
+    }
+}
+
+impl PlayfairCypher {
+
+    /// Returns the step taken for a pair.
+    fn trace_pair(&self, pair: [char; 2], encode: bool) -> Result<TraceStep, PlayfairError> {
+        let board = self.board();
+        let input_positions = [board.get_position(pair[0])?, board.get_position(pair[1])?];
+        let shape = BoardShape::from_positions(input_positions[0], input_positions[1]);
+        let output = if encode { self.encode_pair(pair)? } else { self.decode_pair(pair)? };
+
+        Ok(TraceStep {
+            input: pair,
+            input_positions,
+            shape,
+            rule: TraceRule::init(shape, encode),
+            output,
+            output_positions: [board.get_position(output[0])?, board.get_position(output[1])?],
+        })
+    }
+
+    /// Returns every step of encrypting the message, one per pair, like `encrypt`.
+    pub fn trace_encrypt(&self, message: &str) -> Result<Vec<TraceStep>, PlayfairError> {
+        self.digraphs().pairs(message)?.into_iter().map(|x| self.trace_pair(x, true)).collect()
+    }
+
+    /// Returns every step of decrypting the ciphertext, one per pair, like `decrypt`.
+    pub fn trace_decrypt(&self, ciphertext: &str) -> Result<Vec<TraceStep>, PlayfairError> {
+        self.digraphs().cipher_pairs(ciphertext)?.into_iter().map(|x| self.trace_pair(x, false)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::playfair::array::BoardShape;
+    use crate::playfair::play::PlayfairCypher;
+    use crate::playfair::trace::TraceRule;
+
+    #[test]
+    fn trace_encrypt() {
+        // P L A Y F
+        // I R E X M
+        // B C D G H
+        // K N O Q S
+        // T U V W Z
+        let cypher = PlayfairCypher::init("playfair example").unwrap();
+        let steps = cypher.trace_encrypt("hide plan").unwrap();
+
+        assert_eq!(['H', 'I'], steps[0].input);
+        assert_eq!([[2, 4], [1, 0]], steps[0].input_positions);
+        assert_eq!(BoardShape::RECTANGLE, steps[0].shape);
+        assert_eq!(['B', 'M'], steps[0].output);
+        assert_eq!([[2, 0], [1, 4]], steps[0].output_positions);
+        assert_eq!((BoardShape::COLUMN, TraceRule::DOWN), (steps[1].shape, steps[1].rule));
+        assert_eq!((BoardShape::ROW, TraceRule::RIGHT), (steps[2].shape, steps[2].rule));
+        assert_eq!("HI RECTANGLE (2,4) (1,0) swap corners -> BM (2,0) (1,4)", steps[0].to_string());
+
+        let encrypted: String = steps.iter().flat_map(|x| x.output).collect();
+        assert_eq!(cypher.encrypt("hide plan").unwrap(), encrypted);
+    }
+
+    #[test]
+    fn trace_decrypt() {
+        let cypher = PlayfairCypher::init("playfair example").unwrap();
+        let steps = cypher.trace_decrypt("BMODLA").unwrap();
+
+        assert_eq!(TraceRule::UP, steps[1].rule);
+        assert_eq!(TraceRule::LEFT, steps[2].rule);
+        assert_eq!("HIDEPL", steps.iter().flat_map(|x| x.output).collect::<String>());
+    }
+}